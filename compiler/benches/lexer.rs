@@ -0,0 +1,64 @@
+//! Measures the throughput of the lexer on different kinds of input.
+//!
+//! Run with `cargo bench --bench lexer`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use compiler::cst::lexer::Lexer;
+use compiler::cst::token::KeywordKind;
+
+/// Repeats the line until the text is about one megabyte.
+fn repeat(line: &str) -> String {
+    line.repeat(1_000_000 / line.len())
+}
+
+/// Runs the function until the measurement time is over and prints the throughput of the given
+/// number of bytes.
+fn measure(name: &str, bytes: usize, mut function: impl FnMut()) {
+    let mut iterations = 0u32;
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(2) {
+        function();
+        iterations += 1;
+    }
+    let elapsed = start.elapsed() / iterations;
+    let throughput = bytes as f64 / elapsed.as_secs_f64() / 1_000_000.0;
+    println!("{name:<24} {elapsed:>12.2?} per iteration {throughput:>10.1} MB/s");
+}
+
+/// Lexes the text and prints the throughput.
+fn bench(name: &str, text: &str) {
+    measure(name, text.len(), || {
+        black_box(Lexer::tokenize(black_box(text)));
+    });
+}
+
+/// Compares the keyword lookup by length with comparing against every keyword in turn.
+fn bench_keyword_lookup() {
+    let words = "foo bar_baz x1 contents functional returned iffy elsewhere "
+        .split(' ')
+        .chain(KeywordKind::ALL.map(KeywordKind::as_str))
+        .collect::<Vec<_>>();
+    let bytes = words.iter().map(|word| word.len()).sum();
+    measure("keyword lookup", bytes, || {
+        for word in &words {
+            black_box(KeywordKind::try_from(black_box(*word)).ok());
+        }
+    });
+    measure("keyword lookup (linear)", bytes, || {
+        for word in &words {
+            black_box(KeywordKind::ALL.into_iter().find(|keyword| keyword.as_str() == black_box(*word)));
+        }
+    });
+}
+
+fn main() {
+    let keywords = KeywordKind::ALL.map(|keyword| keyword.as_str()).join(" ");
+    bench("identifiers", &repeat("foo bar_baz x1 contents functional returned iffy elsewhere\n"));
+    bench("keywords", &repeat(&format!("{keywords}\n")));
+    bench("punctuation", &repeat("(a, b) -> {c::d[e] == f + g;}\n"));
+    let hashes = "#".repeat(64);
+    bench("raw strings", &repeat(&format!("r{hashes}\"\"{} \"{}\"{hashes}\n", &hashes[1..], &hashes[32..])));
+    bench("mixed", &repeat("function add(x: Integer, y: Integer) -> Integer { return x + y; }\n"));
+    bench_keyword_lookup();
+}
@@ -0,0 +1,18 @@
+//! Checks that the lexer never panics and that the tokens cover the source code.
+//!
+//! Run with `cargo fuzz run lexer` from the `compiler` directory.
+
+#![no_main]
+
+use compiler::cst::lexer::Lexer;
+use compiler::cst::token::TokenKind;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    for lexer in [Lexer::new(&text), Lexer::with_grapheme_clusters(&text)] {
+        let tokens = lexer.collect::<Vec<_>>();
+        assert_eq!(tokens.last().map(|token| token.kind()), Some(TokenKind::EndOfFile));
+        assert_eq!(tokens.iter().map(|token| token.text()).collect::<String>(), text);
+    }
+});
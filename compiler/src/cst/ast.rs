@@ -0,0 +1,957 @@
+//! Typed wrappers around syntax nodes.
+//!
+//! Each wrapper corresponds to a [`SyntaxKind`] and provides accessors for the children of that
+//! kind of node. Accessors return `None` when a child is missing, as the tree may contain errors.
+
+use super::{KeywordKind, TokenKind};
+use super::syntax::{SyntaxNode, SyntaxToken};
+use super::tree::SyntaxKind;
+
+/// A typed wrapper around a syntax node of a specific kind.
+pub trait AstNode: Sized {
+    /// Wrap the node if it is of the right kind.
+    fn cast(node: SyntaxNode) -> Option<Self>;
+
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+/// A node which can have attributes, such as `@inline`, attached to it.
+pub trait HasAttributes: AstNode {
+    fn attributes(&self) -> impl Iterator<Item=Attribute> {
+        children(self.syntax())
+    }
+}
+
+/// A declaration which can be declared `public` or `private`.
+pub trait HasVisibility: AstNode {
+    /// Returns the visibility of the first visibility keyword, or `None` if there is none.
+    fn visibility(&self) -> Option<Visibility> {
+        self.syntax().children_with_tokens()
+            .filter_map(|child| child.as_token().cloned())
+            .find_map(|token| match token.kind() {
+                TokenKind::Keyword(KeywordKind::Public) => Some(Visibility::Public),
+                TokenKind::Keyword(KeywordKind::Private) => Some(Visibility::Private),
+                _ => None
+            })
+    }
+}
+
+macro_rules! ast_node {
+    ($(#[$attribute:meta])* $name:ident, $kind:ident) => {
+        $(#[$attribute])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name(SyntaxNode);
+
+        impl AstNode for $name {
+            fn cast(node: SyntaxNode) -> Option<Self> {
+                (node.kind() == SyntaxKind::$kind).then(|| Self(node))
+            }
+
+            fn syntax(&self) -> &SyntaxNode {
+                &self.0
+            }
+        }
+    };
+}
+
+ast_node!(
+    /// The root of the syntax tree of a file.
+    SourceFile, File
+);
+ast_node!(ModuleDecl, Module);
+ast_node!(Attribute, Attribute);
+ast_node!(ClassDecl, Class);
+ast_node!(EnumDecl, Enum);
+ast_node!(Variant, Variant);
+ast_node!(StructDecl, Struct);
+ast_node!(StructField, StructField);
+ast_node!(Inherits, Inherits);
+ast_node!(FunctionDecl, Function);
+ast_node!(ParameterList, Parameters);
+ast_node!(Parameter, Parameter);
+ast_node!(GenericParameterList, GenericParameters);
+ast_node!(GenericParameter, GenericParameter);
+ast_node!(GenericArgumentList, GenericArguments);
+ast_node!(FieldDecl, Field);
+ast_node!(Type, Type);
+ast_node!(LiteralExpr, LiteralExpression);
+ast_node!(PrefixExpr, PrefixExpression);
+ast_node!(BinaryExpr, BinaryExpression);
+ast_node!(PathExpr, PathExpression);
+ast_node!(CallExpr, CallExpression);
+ast_node!(ParenExpr, ParenthesizedExpression);
+ast_node!(IfExpr, IfExpression);
+ast_node!(ForLoop, ForLoop);
+ast_node!(MatchExpr, MatchExpression);
+ast_node!(MatchArm, MatchArm);
+ast_node!(BindingPattern, BindingPattern);
+ast_node!(WildcardPattern, WildcardPattern);
+ast_node!(LiteralPattern, LiteralPattern);
+ast_node!(ArgumentList, Arguments);
+ast_node!(BlockExpr, BlockExpression);
+ast_node!(Statement, Statement);
+ast_node!(ReturnStmt, ReturnStatement);
+
+/// A declaration in a file or a class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Item {
+    Module(ModuleDecl),
+    Class(ClassDecl),
+    Enum(EnumDecl),
+    Struct(StructDecl),
+    Function(FunctionDecl),
+    Field(FieldDecl),
+}
+
+impl AstNode for Item {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let item = match node.kind() {
+            SyntaxKind::Module => Item::Module(ModuleDecl(node)),
+            SyntaxKind::Class => Item::Class(ClassDecl(node)),
+            SyntaxKind::Enum => Item::Enum(EnumDecl(node)),
+            SyntaxKind::Struct => Item::Struct(StructDecl(node)),
+            SyntaxKind::Function => Item::Function(FunctionDecl(node)),
+            SyntaxKind::Field => Item::Field(FieldDecl(node)),
+            _ => return None
+        };
+        Some(item)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Item::Module(module) => module.syntax(),
+            Item::Class(class) => class.syntax(),
+            Item::Enum(enum_) => enum_.syntax(),
+            Item::Struct(struct_) => struct_.syntax(),
+            Item::Function(function) => function.syntax(),
+            Item::Field(field) => field.syntax(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    Binding(BindingPattern),
+    Wildcard(WildcardPattern),
+    Literal(LiteralPattern),
+}
+
+impl AstNode for Pattern {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let pattern = match node.kind() {
+            SyntaxKind::BindingPattern => Pattern::Binding(BindingPattern(node)),
+            SyntaxKind::WildcardPattern => Pattern::Wildcard(WildcardPattern(node)),
+            SyntaxKind::LiteralPattern => Pattern::Literal(LiteralPattern(node)),
+            _ => return None
+        };
+        Some(pattern)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Pattern::Binding(binding) => binding.syntax(),
+            Pattern::Wildcard(wildcard) => wildcard.syntax(),
+            Pattern::Literal(literal) => literal.syntax(),
+        }
+    }
+}
+
+impl HasAttributes for Item {}
+impl HasAttributes for ModuleDecl {}
+impl HasAttributes for ClassDecl {}
+impl HasAttributes for EnumDecl {}
+impl HasAttributes for StructDecl {}
+impl HasAttributes for FunctionDecl {}
+impl HasAttributes for FieldDecl {}
+
+impl HasVisibility for Item {}
+impl HasVisibility for ModuleDecl {}
+impl HasVisibility for ClassDecl {}
+impl HasVisibility for EnumDecl {}
+impl HasVisibility for StructDecl {}
+impl HasVisibility for FunctionDecl {}
+impl HasVisibility for FieldDecl {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    Public,
+    Private,
+}
+
+/// The mutability declared by the modifiers of a field or a parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mutability {
+    Immutable,
+    Mutable,
+    Constant,
+    /// Both `constant` and `mutable`, which is not allowed.
+    ConstantMutable,
+}
+
+impl Mutability {
+    /// Returns the mutability declared by the given modifiers.
+    fn of(modifiers: impl Iterator<Item=SyntaxToken>) -> Self {
+        let (mut constant, mut mutable) = (false, false);
+        for modifier in modifiers {
+            match modifier.kind() {
+                TokenKind::Keyword(KeywordKind::Constant) => constant = true,
+                TokenKind::Keyword(KeywordKind::Mutable) => mutable = true,
+                _ => {}
+            }
+        }
+        match (constant, mutable) {
+            (false, false) => Mutability::Immutable,
+            (false, true) => Mutability::Mutable,
+            (true, false) => Mutability::Constant,
+            (true, true) => Mutability::ConstantMutable,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expression {
+    Literal(LiteralExpr),
+    Prefix(PrefixExpr),
+    Binary(BinaryExpr),
+    Path(PathExpr),
+    Call(CallExpr),
+    Paren(ParenExpr),
+    If(IfExpr),
+    For(ForLoop),
+    Match(MatchExpr),
+    Block(BlockExpr),
+}
+
+impl AstNode for Expression {
+    fn cast(node: SyntaxNode) -> Option<Self> {
+        let expression = match node.kind() {
+            SyntaxKind::LiteralExpression => Expression::Literal(LiteralExpr(node)),
+            SyntaxKind::PrefixExpression => Expression::Prefix(PrefixExpr(node)),
+            SyntaxKind::BinaryExpression => Expression::Binary(BinaryExpr(node)),
+            SyntaxKind::PathExpression => Expression::Path(PathExpr(node)),
+            SyntaxKind::CallExpression => Expression::Call(CallExpr(node)),
+            SyntaxKind::ParenthesizedExpression => Expression::Paren(ParenExpr(node)),
+            SyntaxKind::IfExpression => Expression::If(IfExpr(node)),
+            SyntaxKind::ForLoop => Expression::For(ForLoop(node)),
+            SyntaxKind::MatchExpression => Expression::Match(MatchExpr(node)),
+            SyntaxKind::BlockExpression => Expression::Block(BlockExpr(node)),
+            _ => return None
+        };
+        Some(expression)
+    }
+
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Expression::Literal(literal) => literal.syntax(),
+            Expression::Prefix(prefix) => prefix.syntax(),
+            Expression::Binary(binary) => binary.syntax(),
+            Expression::Path(path) => path.syntax(),
+            Expression::Call(call) => call.syntax(),
+            Expression::Paren(paren) => paren.syntax(),
+            Expression::If(if_) => if_.syntax(),
+            Expression::For(for_) => for_.syntax(),
+            Expression::Match(match_) => match_.syntax(),
+            Expression::Block(block) => block.syntax(),
+        }
+    }
+}
+
+impl SourceFile {
+    pub fn items(&self) -> impl Iterator<Item=Item> {
+        children(&self.0)
+    }
+}
+
+impl ModuleDecl {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+
+    pub fn parameters(&self) -> Option<ParameterList> {
+        child(&self.0)
+    }
+
+    /// Returns the items declared in the body of the module, if it has one.
+    pub fn items(&self) -> impl Iterator<Item=Item> {
+        children(&self.0)
+    }
+}
+
+impl Attribute {
+    /// Returns the name of the attribute, such as `inline` for `@inline`.
+    pub fn path(&self) -> Option<PathExpr> {
+        child(&self.0)
+    }
+
+    pub fn arguments(&self) -> Option<ArgumentList> {
+        child(&self.0)
+    }
+}
+
+impl ClassDecl {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+
+    pub fn generic_parameters(&self) -> Option<GenericParameterList> {
+        child(&self.0)
+    }
+
+    pub fn parameters(&self) -> Option<ParameterList> {
+        child(&self.0)
+    }
+
+    pub fn inherits(&self) -> Option<Inherits> {
+        child(&self.0)
+    }
+
+    pub fn items(&self) -> impl Iterator<Item=Item> {
+        children(&self.0)
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item=FieldDecl> {
+        children(&self.0)
+    }
+
+    pub fn methods(&self) -> impl Iterator<Item=FunctionDecl> {
+        children(&self.0)
+    }
+}
+
+impl EnumDecl {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+
+    pub fn generic_parameters(&self) -> Option<GenericParameterList> {
+        child(&self.0)
+    }
+
+    pub fn variants(&self) -> impl Iterator<Item=Variant> {
+        children(&self.0)
+    }
+}
+
+impl Variant {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+}
+
+impl StructDecl {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+
+    pub fn generic_parameters(&self) -> Option<GenericParameterList> {
+        child(&self.0)
+    }
+
+    pub fn fields(&self) -> impl Iterator<Item=StructField> {
+        children(&self.0)
+    }
+}
+
+impl StructField {
+    pub fn mutability(&self) -> Mutability {
+        Mutability::of(tokens(&self.0))
+    }
+
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.0)
+    }
+}
+
+impl Inherits {
+    pub fn types(&self) -> impl Iterator<Item=Type> {
+        children(&self.0)
+    }
+}
+
+impl FunctionDecl {
+    pub fn is_constant(&self) -> bool {
+        token(&self.0, TokenKind::Keyword(KeywordKind::Constant)).is_some()
+    }
+
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+
+    pub fn generic_parameters(&self) -> Option<GenericParameterList> {
+        child(&self.0)
+    }
+
+    pub fn parameters(&self) -> Option<ParameterList> {
+        child(&self.0)
+    }
+
+    pub fn return_type(&self) -> Option<Type> {
+        child(&self.0)
+    }
+
+    pub fn body(&self) -> Option<BlockExpr> {
+        child(&self.0)
+    }
+}
+
+impl ParameterList {
+    pub fn parameters(&self) -> impl Iterator<Item=Parameter> {
+        children(&self.0)
+    }
+}
+
+impl GenericParameterList {
+    pub fn parameters(&self) -> impl Iterator<Item=GenericParameter> {
+        children(&self.0)
+    }
+}
+
+impl GenericParameter {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+}
+
+impl GenericArgumentList {
+    pub fn types(&self) -> impl Iterator<Item=Type> {
+        children(&self.0)
+    }
+}
+
+impl Parameter {
+    pub fn is_constant(&self) -> bool {
+        token(&self.0, TokenKind::Keyword(KeywordKind::Constant)).is_some()
+    }
+
+    pub fn is_mutable(&self) -> bool {
+        token(&self.0, TokenKind::Keyword(KeywordKind::Mutable)).is_some()
+    }
+
+    pub fn mutability(&self) -> Mutability {
+        Mutability::of(tokens(&self.0))
+    }
+
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.0)
+    }
+}
+
+impl FieldDecl {
+    pub fn is_constant(&self) -> bool {
+        matches!(self.mutability(), Mutability::Constant | Mutability::ConstantMutable)
+    }
+
+    pub fn is_mutable(&self) -> bool {
+        matches!(self.mutability(), Mutability::Mutable | Mutability::ConstantMutable)
+    }
+
+    pub fn mutability(&self) -> Mutability {
+        // The modifiers before `let` belong to the declaration, not to the field.
+        Mutability::of(tokens(&self.0)
+            .skip_while(|token| token.kind() != TokenKind::Keyword(KeywordKind::Field)))
+    }
+
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.0)
+    }
+
+    pub fn initializer(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+}
+
+impl Type {
+    pub fn path(&self) -> Option<PathExpr> {
+        child(&self.0)
+    }
+
+    pub fn generic_arguments(&self) -> Option<GenericArgumentList> {
+        child(&self.0)
+    }
+}
+
+impl LiteralExpr {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        significant_token(&self.0)
+    }
+}
+
+impl PrefixExpr {
+    pub fn operator(&self) -> Option<SyntaxToken> {
+        significant_token(&self.0)
+    }
+
+    pub fn operand(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+}
+
+impl BinaryExpr {
+    pub fn lhs(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+
+    /// Returns the operator, which is a combined token for operators such as `==`.
+    pub fn operator(&self) -> Option<SyntaxToken> {
+        significant_token(&self.0)
+    }
+
+    pub fn rhs(&self) -> Option<Expression> {
+        children(&self.0).nth(1)
+    }
+}
+
+impl PathExpr {
+    /// Returns the segments of the path, from the outermost qualifier to the name.
+    pub fn segments(&self) -> impl Iterator<Item=SyntaxToken> + use<> {
+        self.0.children_with_tokens()
+            .filter_map(|child| child.as_token().cloned())
+            .filter(|token| token.kind() == TokenKind::Identifier)
+    }
+
+    /// Returns the last segment of the path.
+    pub fn name(&self) -> Option<SyntaxToken> {
+        self.segments().last()
+    }
+}
+
+impl CallExpr {
+    pub fn callee(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+
+    pub fn arguments(&self) -> Option<ArgumentList> {
+        child(&self.0)
+    }
+}
+
+impl ArgumentList {
+    pub fn arguments(&self) -> impl Iterator<Item=Expression> {
+        children(&self.0)
+    }
+}
+
+impl ParenExpr {
+    pub fn expression(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+}
+
+impl IfExpr {
+    /// Returns the condition, which is the expression before the first block.
+    pub fn condition(&self) -> Option<Expression> {
+        child::<Expression>(&self.0).filter(|condition| !matches!(condition, Expression::Block(_)))
+    }
+
+    /// Returns the block which is evaluated if the condition holds.
+    pub fn then_branch(&self) -> Option<BlockExpr> {
+        child(&self.0)
+    }
+
+    /// Returns the block or the chained `if` after `else`.
+    pub fn else_branch(&self) -> Option<Expression> {
+        self.0.children_with_tokens()
+            .skip_while(|child| child.as_token().is_none_or(|token| token.kind() != TokenKind::Keyword(KeywordKind::Else)))
+            .find_map(|child| child.into_node().and_then(Expression::cast))
+    }
+}
+
+impl ForLoop {
+    pub fn pattern(&self) -> Option<Pattern> {
+        child(&self.0)
+    }
+
+    /// Returns the expression after `in`, which is iterated over.
+    pub fn iterable(&self) -> Option<Expression> {
+        child::<Expression>(&self.0).filter(|iterable| !matches!(iterable, Expression::Block(_)))
+    }
+
+    pub fn body(&self) -> Option<BlockExpr> {
+        child(&self.0)
+    }
+}
+
+impl MatchExpr {
+    /// Returns the expression whose value is matched against the patterns of the arms.
+    pub fn scrutinee(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+
+    pub fn arms(&self) -> impl Iterator<Item=MatchArm> {
+        children(&self.0)
+    }
+}
+
+impl MatchArm {
+    pub fn pattern(&self) -> Option<Pattern> {
+        child(&self.0)
+    }
+
+    /// Returns the expression after `->`, which is evaluated if the pattern matches.
+    pub fn expression(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+}
+
+impl BindingPattern {
+    pub fn name(&self) -> Option<SyntaxToken> {
+        token(&self.0, TokenKind::Identifier)
+    }
+}
+
+impl LiteralPattern {
+    pub fn token(&self) -> Option<SyntaxToken> {
+        significant_token(&self.0)
+    }
+}
+
+impl BlockExpr {
+    pub fn statements(&self) -> impl Iterator<Item=Statement> {
+        children(&self.0)
+    }
+
+    /// Returns the last expression if it is not followed by a semicolon, which is the value of
+    /// the block.
+    pub fn tail_expression(&self) -> Option<Expression> {
+        children(&self.0).last()
+    }
+}
+
+impl Statement {
+    /// Returns the expression of an expression statement.
+    pub fn expression(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+
+    /// Returns the field declared by a let statement.
+    pub fn field(&self) -> Option<FieldDecl> {
+        child(&self.0)
+    }
+
+    pub fn return_statement(&self) -> Option<ReturnStmt> {
+        child(&self.0)
+    }
+}
+
+impl ReturnStmt {
+    /// Returns the returned expression, if any.
+    pub fn expression(&self) -> Option<Expression> {
+        child(&self.0)
+    }
+}
+
+/// Returns the first child node of the given type.
+fn child<N: AstNode>(node: &SyntaxNode) -> Option<N> {
+    children(node).next()
+}
+
+/// Returns the child nodes of the given type.
+fn children<N: AstNode>(node: &SyntaxNode) -> impl Iterator<Item=N> + use<N> {
+    node.children().filter_map(N::cast)
+}
+
+/// Returns the first child token which is not trivia.
+fn significant_token(node: &SyntaxNode) -> Option<SyntaxToken> {
+    node.children_with_tokens()
+        .filter_map(|child| child.as_token().cloned())
+        .find(|token| !token.kind().is_trivia())
+}
+
+/// Returns the child tokens of the node.
+fn tokens(node: &SyntaxNode) -> impl Iterator<Item=SyntaxToken> + use<> {
+    node.children_with_tokens().filter_map(|child| child.as_token().cloned())
+}
+
+/// Returns the first child token of the given kind.
+fn token(node: &SyntaxNode, kind: TokenKind) -> Option<SyntaxToken> {
+    node.children_with_tokens()
+        .filter_map(|child| child.as_token().cloned())
+        .find(|token| token.kind() == kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::parser::parse;
+
+    fn file(text: &str) -> SourceFile {
+        let parse = parse(text);
+        assert_eq!(parse.errors(), []);
+        SourceFile::cast(parse.syntax()).unwrap()
+    }
+
+    #[test]
+    fn test_function() {
+        let file = file("constant function foo(mutable self, x: Integer) -> Integer { bar; 1 }");
+        let Some(Item::Function(function)) = file.items().next() else {
+            panic!("expected a function");
+        };
+        assert!(function.is_constant());
+        assert_eq!(function.name().unwrap().text(), "foo");
+        let parameters = function.parameters().unwrap().parameters().collect::<Vec<_>>();
+        assert_eq!(parameters.len(), 2);
+        assert!(parameters[0].is_mutable());
+        assert_eq!(parameters[0].name().unwrap().text(), "self");
+        assert!(parameters[0].ty().is_none());
+        assert!(!parameters[1].is_mutable());
+        assert_eq!(parameters[1].ty().and_then(|ty| ty.path()).and_then(|path| path.name()).unwrap().text(), "Integer");
+        assert_eq!(function.return_type().and_then(|ty| ty.path()).and_then(|path| path.name()).unwrap().text(), "Integer");
+        let body = function.body().unwrap();
+        assert_eq!(body.statements().count(), 1);
+        let Some(Expression::Literal(literal)) = body.tail_expression() else {
+            panic!("expected a literal");
+        };
+        assert_eq!(literal.token().unwrap().text(), "1");
+    }
+
+    #[test]
+    fn test_generics() {
+        let file = file("function map<T, U>(x: Map<T, List<U>>) {}");
+        let Some(Item::Function(function)) = file.items().next() else {
+            panic!("expected a function");
+        };
+        let names = function.generic_parameters().unwrap().parameters()
+            .map(|parameter| parameter.name().unwrap().text().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["T", "U"]);
+        let ty = function.parameters().unwrap().parameters().next().unwrap().ty().unwrap();
+        assert_eq!(ty.path().unwrap().name().unwrap().text(), "Map");
+        let arguments = ty.generic_arguments().unwrap().types().collect::<Vec<_>>();
+        assert_eq!(arguments.len(), 2);
+        assert_eq!(arguments[1].syntax().text(), "List<U>");
+        assert!(arguments[0].generic_arguments().is_none());
+    }
+
+    #[test]
+    fn test_attributes() {
+        let file = file("@test @cfg(debug) function foo() {}");
+        let item = file.items().next().unwrap();
+        let attributes = item.attributes().collect::<Vec<_>>();
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes[0].path().unwrap().name().unwrap().text(), "test");
+        assert!(attributes[0].arguments().is_none());
+        assert_eq!(attributes[1].arguments().unwrap().arguments().count(), 1);
+        let Item::Function(function) = item else {
+            panic!("expected a function");
+        };
+        assert_eq!(function.name().unwrap().text(), "foo");
+        assert_eq!(function.attributes().count(), 2);
+    }
+
+    #[test]
+    fn test_class() {
+        let file = file("module foo;\nclass Foo: Bar { let mutable x: Integer = 1; }");
+        let items = file.items().collect::<Vec<_>>();
+        let [Item::Module(module), Item::Class(class)] = items.as_slice() else {
+            panic!("expected a module and a class");
+        };
+        assert_eq!(module.name().unwrap().text(), "foo");
+        assert_eq!(class.name().unwrap().text(), "Foo");
+        assert_eq!(class.inherits().unwrap().types().count(), 1);
+        let Some(Item::Field(field)) = class.items().next() else {
+            panic!("expected a field");
+        };
+        assert!(field.is_mutable());
+        assert!(!field.is_constant());
+        assert_eq!(field.name().unwrap().text(), "x");
+        assert!(matches!(field.initializer(), Some(Expression::Literal(_))));
+    }
+
+    #[test]
+    fn test_class_members() {
+        let file = file("class Foo {\n    let x: Integer;\n    function get(self) -> Integer { x }\n    let y: Integer = 1;\n}");
+        let Some(Item::Class(class)) = file.items().next() else {
+            panic!("expected a class");
+        };
+        assert_eq!(class.items().count(), 3);
+        let fields = class.fields().collect::<Vec<_>>();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].initializer(), None);
+        assert!(fields[1].initializer().is_some());
+        let methods = class.methods().collect::<Vec<_>>();
+        assert_eq!(methods.len(), 1);
+        assert_eq!(methods[0].name().unwrap().text(), "get");
+    }
+
+    #[test]
+    fn test_enum() {
+        let file = file("enum Color { Red, Green, Blue }");
+        let Some(Item::Enum(enum_)) = file.items().next() else {
+            panic!("expected an enum");
+        };
+        assert_eq!(enum_.name().unwrap().text(), "Color");
+        let variants = enum_.variants()
+            .map(|variant| variant.name().unwrap().text().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(variants, ["Red", "Green", "Blue"]);
+    }
+
+    #[test]
+    fn test_struct() {
+        let file = file("struct Point { x: Integer, mutable y: Integer }");
+        let Some(Item::Struct(struct_)) = file.items().next() else {
+            panic!("expected a struct");
+        };
+        assert_eq!(struct_.name().unwrap().text(), "Point");
+        let fields = struct_.fields().collect::<Vec<_>>();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].name().unwrap().text(), "x");
+        assert_eq!(fields[0].mutability(), Mutability::Immutable);
+        assert_eq!(fields[1].mutability(), Mutability::Mutable);
+        assert_eq!(fields[1].ty().unwrap().syntax().text(), "Integer");
+    }
+
+    #[test]
+    fn test_mutability() {
+        let file = file("let a: A; let constant b: B = 1; let mutable c: C; let mutable constant d: D;");
+        let mutability = file.items()
+            .map(|item| match item {
+                Item::Field(field) => field.mutability(),
+                _ => panic!("expected a field"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(mutability, [
+            Mutability::Immutable,
+            Mutability::Constant,
+            Mutability::Mutable,
+            Mutability::ConstantMutable,
+        ]);
+    }
+
+    #[test]
+    fn test_field_mutability_after_let() {
+        let file = file("class Foo { constant let mutable x: Integer; }");
+        let Some(Item::Class(class)) = file.items().next() else {
+            panic!("expected a class");
+        };
+        let Some(Item::Field(field)) = class.items().next() else {
+            panic!("expected a field");
+        };
+        assert_eq!(field.mutability(), Mutability::Mutable);
+        assert!(!field.is_constant());
+    }
+
+    #[test]
+    fn test_visibility() {
+        let file = file("public module foo; private class Foo { let x: T; } function bar() {}");
+        let visibility = file.items()
+            .map(|item| item.visibility())
+            .collect::<Vec<_>>();
+        assert_eq!(visibility, [Some(Visibility::Public), Some(Visibility::Private), None]);
+    }
+
+    #[test]
+    fn test_binary_expression() {
+        let file = file("let x: Integer = -a == 1;");
+        let Some(Item::Field(field)) = file.items().next() else {
+            panic!("expected a field");
+        };
+        let Some(Expression::Binary(binary)) = field.initializer() else {
+            panic!("expected a binary expression");
+        };
+        assert_eq!(binary.operator().unwrap().kind(), TokenKind::DoubleEquals);
+        let Some(Expression::Prefix(prefix)) = binary.lhs() else {
+            panic!("expected a prefix expression");
+        };
+        assert_eq!(prefix.operator().unwrap().text(), "-");
+        assert!(matches!(prefix.operand(), Some(Expression::Path(_))));
+        assert!(matches!(binary.rhs(), Some(Expression::Literal(_))));
+    }
+
+    #[test]
+    fn test_path_segments() {
+        let file = file("let x: math::Integer = 1;");
+        let Some(Item::Field(field)) = file.items().next() else {
+            panic!("expected a field");
+        };
+        let path = field.ty().and_then(|ty| ty.path()).unwrap();
+        let segments = path.segments().map(|segment| segment.text().to_string()).collect::<Vec<_>>();
+        assert_eq!(segments, ["math", "Integer"]);
+        assert_eq!(path.name().unwrap().text(), "Integer");
+    }
+
+    #[test]
+    fn test_call_expression() {
+        let file = file("let x: Integer = foo(1, bar);");
+        let Some(Item::Field(field)) = file.items().next() else {
+            panic!("expected a field");
+        };
+        let Some(Expression::Call(call)) = field.initializer() else {
+            panic!("expected a call expression");
+        };
+        let Some(Expression::Path(callee)) = call.callee() else {
+            panic!("expected a path");
+        };
+        assert_eq!(callee.name().unwrap().text(), "foo");
+        assert_eq!(call.arguments().unwrap().arguments().count(), 2);
+    }
+
+    #[test]
+    fn test_if_expression() {
+        let file = file("let x: T = if a { 1 } else if b { 2 } else { 3 };");
+        let Some(Item::Field(field)) = file.items().next() else {
+            panic!("expected a field");
+        };
+        let Some(Expression::If(if_)) = field.initializer() else {
+            panic!("expected an if expression");
+        };
+        assert!(matches!(if_.condition(), Some(Expression::Path(_))));
+        assert_eq!(if_.then_branch().unwrap().syntax().text(), "{ 1 }");
+        let Some(Expression::If(else_if)) = if_.else_branch() else {
+            panic!("expected an else if");
+        };
+        assert!(matches!(else_if.else_branch(), Some(Expression::Block(_))));
+        let parse = parse("let x: T = if { 1 };");
+        let if_ = parse.syntax().descendants().find_map(IfExpr::cast).unwrap();
+        assert_eq!(if_.condition(), None);
+        assert_eq!(if_.else_branch(), None);
+    }
+
+    #[test]
+    fn test_for_loop() {
+        let parse = parse("let x: T = for item in items { item };");
+        let for_ = parse.syntax().descendants().find_map(ForLoop::cast).unwrap();
+        let Some(Pattern::Binding(binding)) = for_.pattern() else {
+            panic!("expected a binding");
+        };
+        assert_eq!(binding.name().unwrap().text(), "item");
+        assert_eq!(for_.iterable().unwrap().syntax().text(), "items");
+        assert_eq!(for_.body().unwrap().syntax().text(), "{ item }");
+    }
+
+    #[test]
+    fn test_match() {
+        let parse = parse("let x: T = match x { 1 -> a, y -> { y } _ -> c };");
+        let match_ = parse.syntax().descendants().find_map(MatchExpr::cast).unwrap();
+        assert_eq!(match_.scrutinee().unwrap().syntax().text(), "x");
+        let arms: Vec<_> = match_.arms().collect();
+        assert_eq!(arms.len(), 3);
+        let Some(Pattern::Literal(literal)) = arms[0].pattern() else {
+            panic!("expected a literal pattern");
+        };
+        assert_eq!(literal.token().unwrap().text(), "1");
+        assert!(matches!(arms[1].pattern(), Some(Pattern::Binding(_))));
+        assert!(matches!(arms[1].expression(), Some(Expression::Block(_))));
+        assert!(matches!(arms[2].pattern(), Some(Pattern::Wildcard(_))));
+        assert_eq!(arms[2].expression().unwrap().syntax().text(), "c");
+    }
+
+    #[test]
+    fn test_cast_wrong_kind() {
+        let file = file("module foo;");
+        assert_eq!(ClassDecl::cast(file.syntax().clone()), None);
+        assert_eq!(Item::cast(file.syntax().clone()), None);
+    }
+}
@@ -0,0 +1,110 @@
+//! A compact buffer holding all tokens of some source code.
+
+use crate::cst::{Token, TokenKind};
+use crate::span::Span;
+
+/// All tokens of some source code, as produced by [`super::Lexer::tokenize`].
+///
+/// The kinds and spans are stored in separate vectors, the text of a token is sliced from the
+/// source code when requested.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBuffer<'text> {
+    text: &'text str,
+    kinds: Vec<TokenKind>,
+    spans: Vec<Span>,
+}
+
+impl<'text> TokenBuffer<'text> {
+    pub(super) fn with_capacity(text: &'text str, capacity: usize) -> Self {
+        Self {
+            text,
+            kinds: Vec::with_capacity(capacity),
+            spans: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub(super) fn push(&mut self, kind: TokenKind, span: Span) {
+        self.kinds.push(kind);
+        self.spans.push(span);
+    }
+
+    /// Returns the source code the tokens were created from.
+    pub fn source(&self) -> &'text str {
+        self.text
+    }
+
+    /// Returns the number of tokens, including the end of file token.
+    pub fn len(&self) -> usize {
+        self.kinds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    pub fn kinds(&self) -> &[TokenKind] {
+        &self.kinds
+    }
+
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// Returns the token at the given index.
+    pub fn get(&self, index: usize) -> Option<Token<'text>> {
+        let kind = *self.kinds.get(index)?;
+        let span = self.spans[index];
+        Some(Token {
+            kind,
+            span,
+            text: span.text(self.text),
+        })
+    }
+
+    /// Returns an iterator over the tokens.
+    pub fn iter(&self) -> impl Iterator<Item=Token<'text>> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cst::KeywordKind;
+    use crate::cst::lexer::Lexer;
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let text = "let x = 1;";
+        let buffer = Lexer::tokenize(text);
+        assert_eq!(buffer.source(), text);
+        assert_eq!(buffer.kinds(), [
+            TokenKind::Keyword(KeywordKind::Field),
+            TokenKind::Whitespace,
+            TokenKind::Identifier,
+            TokenKind::Whitespace,
+            TokenKind::Equals,
+            TokenKind::Whitespace,
+            TokenKind::Integer,
+            TokenKind::Semicolon,
+            TokenKind::EndOfFile,
+        ]);
+        assert_eq!(buffer.spans()[2], Span::new(4, 5));
+        assert_eq!(buffer.get(6), Some(Token { kind: TokenKind::Integer, span: Span::new(8, 9), text: "1" }));
+        assert_eq!(buffer.get(9), None);
+    }
+
+    #[test]
+    fn test_tokenize_matches_lexer() {
+        let text = "#!/usr/bin/env zinc\nfunction main() -> Integer { r#\"§\"# §§ 12ab }";
+        let buffer = Lexer::tokenize(text);
+        assert_eq!(buffer.iter().collect::<Vec<_>>(), Lexer::new(text).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_tokenize_empty_input() {
+        let buffer = Lexer::tokenize("");
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.get(0), Some(Token { kind: TokenKind::EndOfFile, span: Span::empty(0), text: "" }));
+    }
+}
@@ -2,8 +2,16 @@
 //!
 //! A cursor is used to iterate over some source code and generate a stream of lexemes.
 
+use std::collections::VecDeque;
 use std::str::Chars;
-use crate::cst::Span;
+use unicode_segmentation::GraphemeCursor;
+use crate::span::Span;
+
+/// A position in the current lexeme to which a cursor can be reset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mark {
+    offset: usize,
+}
 
 /// An iterator to convert source code into a stream of lexemes.
 ///
@@ -12,6 +20,8 @@ use crate::cst::Span;
 pub struct Cursor<'text> {
     text: &'text str,
     iterator: Chars<'text>,
+    /// Characters which have been peeked but not consumed yet, in order.
+    lookahead: VecDeque<char>,
     start_offset: usize,
     length: usize,
 }
@@ -21,22 +31,24 @@ impl<'text> Cursor<'text> {
         Self {
             text,
             iterator: text.chars(),
+            lookahead: VecDeque::new(),
             start_offset: 0,
             length: 0,
         }
     }
 
+    /// Returns the source code being iterated over.
+    pub fn text(&self) -> &'text str {
+        self.text
+    }
+
     /// Returns the current lexeme.
-    pub fn current(&self) -> Span<'text> {
-        Span {
-            text: self.text,
-            start_offset: self.start_offset,
-            length: self.length,
-        }
+    pub fn current(&self) -> Span {
+        Span::new(self.start_offset, self.start_offset + self.length)
     }
 
     /// Close the current lexeme.
-    pub fn close(&mut self) -> Span<'text> {
+    pub fn close(&mut self) -> Span {
         let current = self.current();
         self.start_offset += self.length;
         self.length = 0;
@@ -47,7 +59,8 @@ impl<'text> Cursor<'text> {
     ///
     /// Returns the consumed character.
     pub fn consume(&mut self) -> Option<char> {
-        let next = self.iterator.next()?;
+        let next = self.lookahead.pop_front()
+            .or_else(|| self.iterator.next())?;
         self.length += next.len_utf8();
         Some(next)
     }
@@ -58,21 +71,63 @@ impl<'text> Cursor<'text> {
     pub fn consume_while(&mut self, predicate: impl Fn(char) -> bool) -> usize {
         // TODO: Evaluate whether to return usize or ()
         let mut consumed = 0;
-        while self.peek().is_some_and(|next| predicate(next)) {
+        while self.peek().is_some_and(&predicate) {
             self.consume();
             consumed += 1;
         }
         consumed
     }
 
+    /// Consume the remaining characters of the extended grapheme cluster that the last consumed
+    /// character belongs to.
+    ///
+    /// Returns the number of characters consumed.
+    pub fn consume_grapheme(&mut self) -> usize {
+        let mut consumed = 0;
+        loop {
+            let offset = self.start_offset + self.length;
+            let mut graphemes = GraphemeCursor::new(offset, self.text.len(), true);
+            if graphemes.is_boundary(self.text, 0).unwrap_or(true) || self.consume().is_none() {
+                return consumed;
+            }
+            consumed += 1;
+        }
+    }
+
+    /// Mark the current position, so that characters can be consumed speculatively.
+    pub fn mark(&self) -> Mark {
+        Mark {
+            offset: self.start_offset + self.length,
+        }
+    }
+
+    /// Reset the cursor to a mark, un-consuming everything consumed since.
+    ///
+    /// The mark must have been created in the current lexeme.
+    pub fn reset(&mut self, mark: Mark) {
+        debug_assert!(self.start_offset <= mark.offset, "mark is before the current lexeme");
+        self.iterator = self.text[mark.offset..].chars();
+        self.lookahead.clear();
+        self.length = mark.offset - self.start_offset;
+    }
+
     /// Peek the next character without consuming it.
     pub fn peek(&self) -> Option<char> {
-        self.peek_at_offset(0)
+        self.lookahead.front()
+            .copied()
+            .or_else(|| self.iterator.clone().next())
     }
 
     /// Peek the character at the given offset without consuming it.
-    pub fn peek_at_offset(&self, offset: usize) -> Option<char> {
-        self.iterator.clone().nth(offset)
+    ///
+    /// Every character is decoded once, so peeking at the same or a smaller offset again is
+    /// constant time.
+    pub fn peek_at_offset(&mut self, offset: usize) -> Option<char> {
+        while self.lookahead.len() <= offset {
+            let next = self.iterator.next()?;
+            self.lookahead.push_back(next);
+        }
+        Some(self.lookahead[offset])
     }
 }
 
@@ -84,7 +139,7 @@ mod tests {
     fn test_close_lexeme_without_consuming() {
         let text = "";
         let mut cursor = Cursor::new(text);
-        assert_eq!(cursor.close(), Span { text, start_offset: 0, length: 0 });
+        assert_eq!(cursor.close(), Span::new(0, 0));
     }
 
     #[test]
@@ -92,7 +147,7 @@ mod tests {
         let text = "";
         let mut cursor = Cursor::new(text);
         assert_eq!(cursor.consume(), None);
-        assert_eq!(cursor.close(), Span { text, start_offset: 0, length: 0 });
+        assert_eq!(cursor.close(), Span::new(0, 0));
     }
 
     #[test]
@@ -100,7 +155,7 @@ mod tests {
         let text = "abc";
         let mut cursor = Cursor::new(text);
         assert_eq!(cursor.consume(), Some('a'));
-        assert_eq!(cursor.close(), Span { text, start_offset: 0, length: 1 });
+        assert_eq!(cursor.close(), Span::new(0, 1));
     }
 
     #[test]
@@ -108,7 +163,7 @@ mod tests {
         let text = "aaabc";
         let mut cursor = Cursor::new(text);
         assert_eq!(cursor.consume_while(|next| next == 'a'), 3);
-        assert_eq!(cursor.close(), Span { text, start_offset: 0, length: 3 });
+        assert_eq!(cursor.close(), Span::new(0, 3));
     }
 
     #[test]
@@ -119,7 +174,7 @@ mod tests {
         assert_eq!(cursor.peek(), Some('a'));
         assert_eq!(cursor.consume(), Some('a'));
         assert_eq!(cursor.peek(), Some('b'));
-        assert_eq!(cursor.close(), Span { text, start_offset: 0, length: 1 })
+        assert_eq!(cursor.close(), Span::new(0, 1))
     }
 
     #[test]
@@ -131,7 +186,48 @@ mod tests {
         assert_eq!(cursor.consume(), Some('a'));
         assert_eq!(cursor.peek_at_offset(0), Some('b'));
         assert_eq!(cursor.peek_at_offset(1), Some('c'));
-        assert_eq!(cursor.close(), Span { text, start_offset: 0, length: 1 })
+        assert_eq!(cursor.close(), Span::new(0, 1))
+    }
+
+    #[test]
+    fn test_consume_after_peek_at_offset() {
+        let text = "abcd";
+        let mut cursor = Cursor::new(text);
+        assert_eq!(cursor.peek_at_offset(2), Some('c'));
+        assert_eq!(cursor.peek(), Some('a'));
+        assert_eq!(cursor.consume_while(|next| next != 'd'), 3);
+        assert_eq!(cursor.peek(), Some('d'));
+        assert_eq!(cursor.peek_at_offset(1), None);
+        assert_eq!(cursor.consume(), Some('d'));
+        assert_eq!(cursor.consume(), None);
+        assert_eq!(cursor.close(), Span::new(0, 4))
+    }
+
+    #[test]
+    fn test_reset() {
+        let text = "1..5";
+        let mut cursor = Cursor::new(text);
+        assert_eq!(cursor.consume(), Some('1'));
+        let mark = cursor.mark();
+        assert_eq!(cursor.consume(), Some('.'));
+        assert_eq!(cursor.peek_at_offset(1), Some('5'));
+        cursor.reset(mark);
+        assert_eq!(cursor.peek(), Some('.'));
+        assert_eq!(cursor.close(), Span::new(0, 1));
+        assert_eq!(cursor.consume_while(|next| next == '.'), 2);
+        assert_eq!(cursor.close(), Span::new(1, 3));
+    }
+
+    #[test]
+    fn test_consume_grapheme() {
+        let text = "e\u{301}\u{301}x";
+        let mut cursor = Cursor::new(text);
+        assert_eq!(cursor.consume(), Some('e'));
+        assert_eq!(cursor.consume_grapheme(), 2);
+        assert_eq!(cursor.consume_grapheme(), 0);
+        assert_eq!(cursor.close(), Span::new(0, "e\u{301}\u{301}".len()));
+        assert_eq!(cursor.consume(), Some('x'));
+        assert_eq!(cursor.consume_grapheme(), 0);
     }
 
     #[test]
@@ -141,6 +237,6 @@ mod tests {
         cursor.consume_while(|_| true);
         // We currently don't handle multiple characters joined together.
         // This might change in the future.
-        assert_eq!(cursor.close(), Span { text, start_offset: 0, length: text.len() });
+        assert_eq!(cursor.close(), Span::new(0, text.len()));
     }
 }
@@ -1,18 +1,28 @@
 //! Lexer responsible for converting source code into a stream of tokens.
 
+mod buffer;
 mod lexeme;
 
 use std::collections::VecDeque;
-use super::{Token, TokenKind, KeywordKind};
+use super::{Token, TokenKind, KeywordKind, LexErrorKind};
 use lexeme::Cursor;
 
+pub use buffer::TokenBuffer;
+
 /// A lexer to convert source code into a stream of tokens.
 ///
 /// The lexer will not return combined tokens. A combined token (e.g. '->') is built up of other
 /// tokens ('-' and '>'). The lexer is not aware whether a combined token is expected.
+///
+/// The last token is always an [`TokenKind::EndOfFile`] token.
+///
+/// Lexing never fails: any input is converted into tokens, using error tokens for text which is
+/// not valid. The text of the tokens concatenated is always equal to the source code.
 pub struct Lexer<'text> {
     cursor: Cursor<'text>,
     queue: VecDeque<Token<'text>>,
+    graphemes: bool,
+    finished: bool,
 }
 
 impl<'text> Lexer<'text> {
@@ -20,13 +30,53 @@ impl<'text> Lexer<'text> {
         Self {
             cursor: Cursor::new(text),
             queue: VecDeque::new(),
+            graphemes: false,
+            finished: false,
         }
     }
 
-    /// Consumes and returns the next token.
-    pub fn next(&mut self) -> Option<Token<'text>> {
-        self.queue.pop_front()
-            .or_else(|| self.create())
+    /// Create a lexer which treats extended grapheme clusters as a single unit inside string
+    /// literals and error tokens.
+    ///
+    /// For example, a family emoji or a flag is never split across tokens, and a quote followed
+    /// by a combining character does not close a string.
+    pub fn with_grapheme_clusters(text: &'text str) -> Self {
+        Self {
+            graphemes: true,
+            ..Self::new(text)
+        }
+    }
+
+    /// Create a lexer which returns the tokens of a buffer, so that source code which has already
+    /// been lexed is not lexed again.
+    pub fn from_tokens(tokens: &TokenBuffer<'text>) -> Self {
+        Self {
+            // All tokens are queued, including the end of file token, so the cursor is never used.
+            cursor: Cursor::new(""),
+            queue: tokens.iter().collect(),
+            graphemes: false,
+            finished: true,
+        }
+    }
+
+    /// Lex the whole source code in one pass into a token buffer.
+    ///
+    /// Faster than collecting the tokens of a lexer when all tokens are needed at once.
+    pub fn tokenize(text: &'text str) -> TokenBuffer<'text> {
+        let mut lexer = Self::new(text);
+        // Tokens are a few characters on average, so this rarely needs to grow.
+        let mut buffer = TokenBuffer::with_capacity(text, text.len() / 4 + 1);
+        while let Some(token) = lexer.create() {
+            buffer.push(token.kind, token.span);
+        }
+        buffer
+    }
+
+    /// Returns an iterator over the tokens which are not trivia.
+    pub fn significant(self) -> SkipTrivia<'text> {
+        SkipTrivia {
+            lexer: self,
+        }
     }
 
     /// Returns the next token without consuming it.
@@ -71,30 +121,56 @@ impl<'text> Lexer<'text> {
     }
 
     fn create(&mut self) -> Option<Token<'text>> {
-        let next = self.cursor.consume()?;
+        let Some(next) = self.cursor.consume() else {
+            return self.end_of_file();
+        };
         let kind = match next {
+            BYTE_ORDER_MARK if self.cursor.current().start() == 0 => TokenKind::ByteOrderMark,
             next if is_whitespace(next) => self.whitespace(),
+            '#' if self.is_shebang_start() => self.shebang(),
+            'r' if self.is_raw_string_start() => self.raw_string(),
             next if is_identifier_start(next) => self.identifier(),
             next if is_integer(next) => self.integer(),
-            ',' => TokenKind::Comma,
-            ';' => TokenKind::Semicolon,
-            ':' => TokenKind::Colon,
-            '=' => TokenKind::Equals,
-            '-' => TokenKind::Minus,
-            '>' => TokenKind::GreaterThan,
-            '{' => TokenKind::LeftBrace,
-            '}' => TokenKind::RightBrace,
-            '(' => TokenKind::LeftParentheses,
-            ')' => TokenKind::RightParentheses,
-            _ => TokenKind::Unknown
+            next => punctuation(next)
+                .unwrap_or_else(|| self.unexpected())
         };
         let span = self.cursor.close();
         Some(Token {
             kind,
             span,
+            text: span.text(self.cursor.text()),
+        })
+    }
+
+    /// Returns an empty end of file token the first time the end of the source code is reached.
+    fn end_of_file(&mut self) -> Option<Token<'text>> {
+        if self.finished {
+            return None;
+        }
+        self.finished = true;
+        Some(Token {
+            kind: TokenKind::EndOfFile,
+            span: self.cursor.close(),
+            text: "",
         })
     }
 
+    /// Unexpected characters are merged into a single error token.
+    ///
+    /// The error token ends at the next character which starts a valid token.
+    fn unexpected(&mut self) -> TokenKind {
+        if self.graphemes {
+            self.cursor.consume_grapheme();
+            while self.cursor.peek().is_some_and(is_unexpected) {
+                self.cursor.consume();
+                self.cursor.consume_grapheme();
+            }
+        } else {
+            self.cursor.consume_while(is_unexpected);
+        }
+        TokenKind::Error(LexErrorKind::UnexpectedCharacter)
+    }
+
     fn whitespace(&mut self) -> TokenKind {
         self.cursor.consume_while(is_whitespace);
         TokenKind::Whitespace
@@ -103,37 +179,148 @@ impl<'text> Lexer<'text> {
     fn identifier(&mut self) -> TokenKind {
         self.cursor.consume_while(is_identifier_continue);
         let span = self.cursor.current();
-        if let Ok(keyword) = KeywordKind::try_from(span.text()) {
-            TokenKind::Keyword(keyword)
-        } else {
-            TokenKind::Identifier
+        match KeywordKind::try_from(span.text(self.cursor.text())) {
+            Ok(keyword) if !keyword.is_contextual() => TokenKind::Keyword(keyword),
+            _ => TokenKind::Identifier
         }
     }
 
+    /// Check if the upcoming characters (after a `#`) open a shebang.
+    ///
+    /// A shebang can only appear at the very start of the source code, after a byte order mark if
+    /// there is one. `#![` is not a shebang so that the syntax stays available for attributes.
+    fn is_shebang_start(&mut self) -> bool {
+        let start = self.cursor.current().start();
+        (start == 0 || start == BYTE_ORDER_MARK.len_utf8() && self.cursor.text().starts_with(BYTE_ORDER_MARK))
+            && self.cursor.peek() == Some('!')
+            && self.cursor.peek_at_offset(1) != Some('[')
+    }
+
+    /// A shebang runs until the end of the line. The line break is not part of the shebang.
+    fn shebang(&mut self) -> TokenKind {
+        self.cursor.consume_while(|next| next != '\n' && next != '\r');
+        TokenKind::Shebang
+    }
+
+    /// Check if the upcoming characters (after an `r`) open a raw string.
+    fn is_raw_string_start(&mut self) -> bool {
+        let mark = self.cursor.mark();
+        self.cursor.consume_while(|next| next == '#');
+        let quote = self.cursor.peek() == Some('"');
+        self.cursor.reset(mark);
+        quote
+    }
+
+    /// A raw string is closed by a quote followed by as many hashes as it was opened with.
+    fn raw_string(&mut self) -> TokenKind {
+        let hashes = self.cursor.consume_while(|next| next == '#');
+        self.cursor.consume();
+        while let Some(next) = self.cursor.consume() {
+            let extended = self.graphemes && self.cursor.consume_grapheme() > 0;
+            let closed = next == '"' && !extended && (0..hashes)
+                .all(|offset| self.cursor.peek_at_offset(offset) == Some('#'));
+            if closed {
+                for _ in 0..hashes {
+                    self.cursor.consume();
+                }
+                return TokenKind::RawString;
+            }
+        }
+        TokenKind::Error(LexErrorKind::UnterminatedString)
+    }
+
     fn integer(&mut self) -> TokenKind {
         self.cursor.consume_while(is_integer);
-        TokenKind::Integer
+        if self.cursor.consume_while(is_identifier_continue) > 0 {
+            TokenKind::Error(LexErrorKind::InvalidDigit)
+        } else {
+            TokenKind::Integer
+        }
     }
 }
 
 impl<'text> Iterator for Lexer<'text> {
     type Item = Token<'text>;
 
+    /// Consumes and returns the next token.
+    fn next(&mut self) -> Option<Self::Item> {
+        self.queue.pop_front()
+            .or_else(|| self.create())
+    }
+}
+
+/// An iterator over the tokens of a lexer which skips trivia, such as whitespace.
+///
+/// The spans of the returned tokens still refer to their position in the source code.
+pub struct SkipTrivia<'text> {
+    lexer: Lexer<'text>,
+}
+
+impl<'text> Iterator for SkipTrivia<'text> {
+    type Item = Token<'text>;
+
     fn next(&mut self) -> Option<Self::Item> {
-        self.next()
+        self.lexer.find(|token| !token.kind.is_trivia())
     }
 }
 
+const BYTE_ORDER_MARK: char = '\u{FEFF}';
+
+/// Returns the kind of single character punctuation token, if any.
+fn punctuation(next: char) -> Option<TokenKind> {
+    let kind = match next {
+        ',' => TokenKind::Comma,
+        ';' => TokenKind::Semicolon,
+        ':' => TokenKind::Colon,
+        '=' => TokenKind::Equals,
+        '+' => TokenKind::Plus,
+        '-' => TokenKind::Minus,
+        '*' => TokenKind::Asterisk,
+        '/' => TokenKind::Slash,
+        '%' => TokenKind::Percent,
+        '!' => TokenKind::Exclamation,
+        '&' => TokenKind::Ampersand,
+        '|' => TokenKind::Pipe,
+        '^' => TokenKind::Caret,
+        '~' => TokenKind::Tilde,
+        '<' => TokenKind::LessThan,
+        '>' => TokenKind::GreaterThan,
+        '{' => TokenKind::LeftBrace,
+        '}' => TokenKind::RightBrace,
+        '(' => TokenKind::LeftParentheses,
+        ')' => TokenKind::RightParentheses,
+        '[' => TokenKind::LeftBracket,
+        ']' => TokenKind::RightBracket,
+        '?' => TokenKind::Question,
+        '@' => TokenKind::At,
+        '#' => TokenKind::Hash,
+        '$' => TokenKind::Dollar,
+        '\\' => TokenKind::Backslash,
+        '`' => TokenKind::Backtick,
+        _ => return None
+    };
+    Some(kind)
+}
+
+/// Check if the character does not start any token.
+fn is_unexpected(next: char) -> bool {
+    !is_whitespace(next)
+        && !is_identifier_start(next)
+        && !is_integer(next)
+        && punctuation(next).is_none()
+}
+
 fn is_whitespace(next: char) -> bool {
     next.is_whitespace()
 }
 
+/// Identifiers follow Unicode's `XID_Start` and `XID_Continue` properties (UAX #31).
 fn is_identifier_start(next: char) -> bool {
-    next == '_' || next.is_ascii_alphabetic()
+    next == '_' || unicode_ident::is_xid_start(next)
 }
 
 fn is_identifier_continue(next: char) -> bool {
-    is_identifier_start(next) || next.is_ascii_digit()
+    unicode_ident::is_xid_continue(next)
 }
 
 fn is_integer(next: char) -> bool {
@@ -143,15 +330,16 @@ fn is_integer(next: char) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::cst::Span;
+    use crate::span::Span;
 
     #[test]
     fn test_next() {
         let text = "foo 123";
         let mut lexer = Lexer::new(text);
-        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "foo".len() } }), lexer.next());
-        assert_eq!(Some(Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 3, length: " ".len() } }), lexer.next());
-        assert_eq!(Some(Token { kind: TokenKind::Integer, span: Span { text, start_offset: 4, length: "123".len() } }), lexer.next());
+        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span::new(0, "foo".len()), text: "foo" }), lexer.next());
+        assert_eq!(Some(Token { kind: TokenKind::Whitespace, span: Span::new(3, 3 + " ".len()), text: " " }), lexer.next());
+        assert_eq!(Some(Token { kind: TokenKind::Integer, span: Span::new(4, 4 + "123".len()), text: "123" }), lexer.next());
+        assert_eq!(Some(Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" }), lexer.next());
         assert_eq!(None, lexer.next());
     }
 
@@ -159,40 +347,44 @@ mod tests {
     fn test_peek() {
         let text = "foo bar";
         let mut lexer = Lexer::new(text);
-        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "foo".len() } }), lexer.peek());
-        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "foo".len() } }), lexer.peek());
-        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "foo".len() } }), lexer.next());
-        assert_eq!(Some(Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 3, length: " ".len() } }), lexer.peek());
+        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span::new(0, "foo".len()), text: "foo" }), lexer.peek());
+        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span::new(0, "foo".len()), text: "foo" }), lexer.peek());
+        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span::new(0, "foo".len()), text: "foo" }), lexer.next());
+        assert_eq!(Some(Token { kind: TokenKind::Whitespace, span: Span::new(3, 3 + " ".len()), text: " " }), lexer.peek());
     }
 
     #[test]
     fn test_peek_at_offset() {
         let text = "foo bar";
         let mut lexer = Lexer::new(text);
-        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "foo".len() } }), lexer.peek_at_offset(0));
-        assert_eq!(Some(Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 3, length: " ".len() } }), lexer.peek_at_offset(1));
-        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 4, length: "bar".len() } }), lexer.peek_at_offset(2));
-        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "foo".len() } }), lexer.next());
-        assert_eq!(Some(Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 3, length: " ".len() } }), lexer.peek());
+        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span::new(0, "foo".len()), text: "foo" }), lexer.peek_at_offset(0));
+        assert_eq!(Some(Token { kind: TokenKind::Whitespace, span: Span::new(3, 3 + " ".len()), text: " " }), lexer.peek_at_offset(1));
+        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span::new(4, 4 + "bar".len()), text: "bar" }), lexer.peek_at_offset(2));
+        assert_eq!(Some(Token { kind: TokenKind::Identifier, span: Span::new(0, "foo".len()), text: "foo" }), lexer.next());
+        assert_eq!(Some(Token { kind: TokenKind::Whitespace, span: Span::new(3, 3 + " ".len()), text: " " }), lexer.peek());
     }
 
     #[test]
     fn test_empty_input() {
         let text = "";
         let mut lexer = Lexer::new(text);
-        assert_eq!(None, lexer.peek());
+        assert_eq!(Some(Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" }), lexer.peek());
         assert_eq!(None, lexer.peek_at_offset(1));
-        assert_eq!(None, lexer.next());
+        assert_eq!(Some(Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" }), lexer.next());
         assert_eq!(None, lexer.peek());
+        assert_eq!(None, lexer.next());
     }
 
     #[test]
-    fn test_unknown() {
+    fn test_unexpected_character() {
         let text = "§";
         let lexer = Lexer::new(text);
         assert_eq!(
             lexer.collect::<Vec<_>>(),
-            vec![Token { kind: TokenKind::Unknown, span: Span { text, start_offset: 0, length: "§".len() } }]
+            vec![
+                Token { kind: TokenKind::Error(LexErrorKind::UnexpectedCharacter), span: Span::new(0, "§".len()), text: "§" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
         );
     }
 
@@ -200,18 +392,84 @@ mod tests {
     fn test_emoji() {
         let text = "👨‍👩‍👧‍👦";
         let lexer = Lexer::new(text);
-        // We currently don't handle multiple characters joined together.
-        // As a result, we return all characters separately.
+        // None of the characters start a token, so they are merged into a single error.
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Error(LexErrorKind::UnexpectedCharacter), span: Span::new(0, text.len()), text },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grapheme_cluster_error() {
+        // U+0600 (Arabic number sign) is a prepended character, it forms a cluster with 'a'.
+        let text = "\u{600}a";
+        assert_eq!(
+            Lexer::new(text).collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Error(LexErrorKind::UnexpectedCharacter), span: Span::new(0, "\u{600}".len()), text: "\u{600}" },
+                Token { kind: TokenKind::Identifier, span: Span::new(2, 2 + "a".len()), text: "a" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+        assert_eq!(
+            Lexer::with_grapheme_clusters(text).collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Error(LexErrorKind::UnexpectedCharacter), span: Span::new(0, text.len()), text },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grapheme_cluster_emoji() {
+        let text = "👨‍👩‍👧‍👦🇸🇪";
+        let lexer = Lexer::with_grapheme_clusters(text);
         assert_eq!(
             lexer.collect::<Vec<_>>(),
             vec![
-                Token { kind: TokenKind::Unknown, span: Span { text, start_offset: 0, length: "👨".len() } },
-                Token { kind: TokenKind::Unknown, span: Span { text, start_offset: 4, length: "‍".len() } },
-                Token { kind: TokenKind::Unknown, span: Span { text, start_offset: 7, length: "👩".len() } },
-                Token { kind: TokenKind::Unknown, span: Span { text, start_offset: 11, length: "‍".len() } },
-                Token { kind: TokenKind::Unknown, span: Span { text, start_offset: 14, length: "👧".len() } },
-                Token { kind: TokenKind::Unknown, span: Span { text, start_offset: 18, length: "‍".len() } },
-                Token { kind: TokenKind::Unknown, span: Span { text, start_offset: 21, length: "👦".len() } },
+                Token { kind: TokenKind::Error(LexErrorKind::UnexpectedCharacter), span: Span::new(0, text.len()), text },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_grapheme_cluster_raw_string() {
+        // A quote followed by U+0301 (combining acute accent) does not close the string.
+        let text = "r\"a\"\u{301}\"";
+        assert_eq!(
+            Lexer::new(text).collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::RawString, span: Span::new(0, "r\"a\"".len()), text: "r\"a\"" },
+                Token { kind: TokenKind::Error(LexErrorKind::UnexpectedCharacter), span: Span::new(4, 4 + "\u{301}\"".len()), text: "\u{301}\"" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+        assert_eq!(
+            Lexer::with_grapheme_clusters(text).collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::RawString, span: Span::new(0, text.len()), text },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unexpected_characters_are_merged() {
+        let text = "§¤§ foo§€(";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Error(LexErrorKind::UnexpectedCharacter), span: Span::new(0, "§¤§".len()), text: "§¤§" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(6, 6 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(7, 7 + "foo".len()), text: "foo" },
+                Token { kind: TokenKind::Error(LexErrorKind::UnexpectedCharacter), span: Span::new(10, 10 + "§€".len()), text: "§€" },
+                Token { kind: TokenKind::LeftParentheses, span: Span::new(15, 15 + "(".len()), text: "(" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
             ]
         );
     }
@@ -222,7 +480,10 @@ mod tests {
         let lexer = Lexer::new(text);
         assert_eq!(
             lexer.collect::<Vec<_>>(),
-            vec![Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "foo".len() } }]
+            vec![
+                Token { kind: TokenKind::Identifier, span: Span::new(0, "foo".len()), text: "foo" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
         );
     }
 
@@ -232,7 +493,10 @@ mod tests {
         let lexer = Lexer::new(text);
         assert_eq!(
             lexer.collect::<Vec<_>>(),
-            vec![Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "foo123".len() } }]
+            vec![
+                Token { kind: TokenKind::Identifier, span: Span::new(0, "foo123".len()), text: "foo123" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
         );
     }
 
@@ -242,7 +506,10 @@ mod tests {
         let lexer = Lexer::new(text);
         assert_eq!(
             lexer.collect::<Vec<_>>(),
-            vec![Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "foo_bar".len() } }]
+            vec![
+                Token { kind: TokenKind::Identifier, span: Span::new(0, "foo_bar".len()), text: "foo_bar" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
         );
     }
 
@@ -252,7 +519,54 @@ mod tests {
         let lexer = Lexer::new(text);
         assert_eq!(
             lexer.collect::<Vec<_>>(),
-            vec![Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "_foo".len() } }]
+            vec![
+                Token { kind: TokenKind::Identifier, span: Span::new(0, "_foo".len()), text: "_foo" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identifier_unicode() {
+        let text = "größe 変数";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Identifier, span: Span::new(0, "größe".len()), text: "größe" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(7, 7 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(8, 8 + "変数".len()), text: "変数" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identifier_combining_character() {
+        // 'e' followed by U+0301 (combining acute accent).
+        let text = "cafe\u{301}";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Identifier, span: Span::new(0, "cafe\u{301}".len()), text: "cafe\u{301}" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_identifier_starts_with_combining_character() {
+        // A combining character can continue but not start an identifier.
+        let text = "\u{301}a";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Error(LexErrorKind::UnexpectedCharacter), span: Span::new(0, "\u{301}".len()), text: "\u{301}" },
+                Token { kind: TokenKind::Identifier, span: Span::new(2, 2 + "a".len()), text: "a" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
         );
     }
 
@@ -262,7 +576,10 @@ mod tests {
         let lexer = Lexer::new(text);
         assert_eq!(
             lexer.collect::<Vec<_>>(),
-            vec![Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 0, length: " \n\n \t ".len() } }]
+            vec![
+                Token { kind: TokenKind::Whitespace, span: Span::new(0, " \n\n \t ".len()), text: " \n\n \t " },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
         );
     }
 
@@ -273,11 +590,153 @@ mod tests {
         assert_eq!(
             lexer.collect::<Vec<_>>(),
             vec![
-                Token { kind: TokenKind::Integer, span: Span { text, start_offset: 0, length: "123".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 3, length: " ".len() } },
-                Token { kind: TokenKind::Integer, span: Span { text, start_offset: 4, length: "456".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 7, length: " ".len() } },
-                Token { kind: TokenKind::Integer, span: Span { text, start_offset: 8, length: "0".len() } },
+                Token { kind: TokenKind::Integer, span: Span::new(0, "123".len()), text: "123" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(3, 3 + " ".len()), text: " " },
+                Token { kind: TokenKind::Integer, span: Span::new(4, 4 + "456".len()), text: "456" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(7, 7 + " ".len()), text: " " },
+                Token { kind: TokenKind::Integer, span: Span::new(8, 8 + "0".len()), text: "0" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_string() {
+        let text = r##"r"C:\path" r#"say "hi""#"##;
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::RawString, span: Span::new(0, r#"r"C:\path""#.len()), text: r#"r"C:\path""# },
+                Token { kind: TokenKind::Whitespace, span: Span::new(10, 10 + " ".len()), text: " " },
+                Token { kind: TokenKind::RawString, span: Span::new(11, 11 + r###"r#"say "hi""#"###.len()), text: r###"r#"say "hi""#"### },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_string_unterminated() {
+        let text = r#"foo r#"bar" baz"#;
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Identifier, span: Span::new(0, "foo".len()), text: "foo" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(3, 3 + " ".len()), text: " " },
+                Token { kind: TokenKind::Error(LexErrorKind::UnterminatedString), span: Span::new(4, 4 + r##"r#"bar" baz"##.len()), text: r##"r#"bar" baz"## },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_string_prefix_is_identifier() {
+        let text = "r rx r#x";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Identifier, span: Span::new(0, "r".len()), text: "r" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(1, 1 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(2, 2 + "rx".len()), text: "rx" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(4, 4 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(5, 5 + "r".len()), text: "r" },
+                Token { kind: TokenKind::Hash, span: Span::new(6, 6 + "#".len()), text: "#" },
+                Token { kind: TokenKind::Identifier, span: Span::new(7, 7 + "x".len()), text: "x" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shebang() {
+        let text = "#!/usr/bin/env zinc\nmodule";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Shebang, span: Span::new(0, "#!/usr/bin/env zinc".len()), text: "#!/usr/bin/env zinc" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(19, 19 + "\n".len()), text: "\n" },
+                Token { kind: TokenKind::Keyword(KeywordKind::Module), span: Span::new(20, 20 + "module".len()), text: "module" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shebang_carriage_return() {
+        let text = "#!/usr/bin/env zinc\r\nmodule";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Shebang, span: Span::new(0, "#!/usr/bin/env zinc".len()), text: "#!/usr/bin/env zinc" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(19, 19 + "\r\n".len()), text: "\r\n" },
+                Token { kind: TokenKind::Keyword(KeywordKind::Module), span: Span::new(21, 21 + "module".len()), text: "module" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_byte_order_mark() {
+        let text = "\u{FEFF}#!zinc\nmodule\u{FEFF}";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::ByteOrderMark, span: Span::new(0, "\u{FEFF}".len()), text: "\u{FEFF}" },
+                Token { kind: TokenKind::Shebang, span: Span::new(3, 3 + "#!zinc".len()), text: "#!zinc" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(9, 9 + "\n".len()), text: "\n" },
+                Token { kind: TokenKind::Keyword(KeywordKind::Module), span: Span::new(10, 10 + "module".len()), text: "module" },
+                Token { kind: TokenKind::Error(LexErrorKind::UnexpectedCharacter), span: Span::new(16, 16 + "\u{FEFF}".len()), text: "\u{FEFF}" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shebang_only_at_start() {
+        let text = " #!";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Whitespace, span: Span::new(0, " ".len()), text: " " },
+                Token { kind: TokenKind::Hash, span: Span::new(1, 1 + "#".len()), text: "#" },
+                Token { kind: TokenKind::Exclamation, span: Span::new(2, 2 + "!".len()), text: "!" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shebang_not_attribute() {
+        let text = "#![";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Hash, span: Span::new(0, "#".len()), text: "#" },
+                Token { kind: TokenKind::Exclamation, span: Span::new(1, 1 + "!".len()), text: "!" },
+                Token { kind: TokenKind::LeftBracket, span: Span::new(2, 2 + "[".len()), text: "[" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_integer_invalid_digit() {
+        let text = "123abc 4_5";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Error(LexErrorKind::InvalidDigit), span: Span::new(0, "123abc".len()), text: "123abc" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(6, 6 + " ".len()), text: " " },
+                Token { kind: TokenKind::Error(LexErrorKind::InvalidDigit), span: Span::new(7, 7 + "4_5".len()), text: "4_5" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
             ]
         );
     }
@@ -289,17 +748,83 @@ mod tests {
         assert_eq!(
             lexer.collect::<Vec<_>>(),
             vec![
-                Token { kind: TokenKind::Keyword(KeywordKind::Module), span: Span { text, start_offset: 0, length: "module".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 6, length: " ".len() } },
-                Token { kind: TokenKind::Keyword(KeywordKind::Class), span: Span { text, start_offset: 7, length: "class".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 12, length: " ".len() } },
-                Token { kind: TokenKind::Keyword(KeywordKind::Field), span: Span { text, start_offset: 13, length: "let".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 16, length: " ".len() } },
-                Token { kind: TokenKind::Keyword(KeywordKind::Function), span: Span { text, start_offset: 17, length: "function".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 25, length: " ".len() } },
-                Token { kind: TokenKind::Keyword(KeywordKind::Constant), span: Span { text, start_offset: 26, length: "constant".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 34, length: " ".len() } },
-                Token { kind: TokenKind::Keyword(KeywordKind::Mutable), span: Span { text, start_offset: 35, length: "mutable".len() } },
+                Token { kind: TokenKind::Keyword(KeywordKind::Module), span: Span::new(0, "module".len()), text: "module" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(6, 6 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::Class), span: Span::new(7, 7 + "class".len()), text: "class" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(12, 12 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::Field), span: Span::new(13, 13 + "let".len()), text: "let" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(16, 16 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::Function), span: Span::new(17, 17 + "function".len()), text: "function" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(25, 25 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(26, 26 + "constant".len()), text: "constant" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(34, 34 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(35, 35 + "mutable".len()), text: "mutable" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_control_flow_keyword() {
+        let text = "if else while for return break continue";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Keyword(KeywordKind::If), span: Span::new(0, "if".len()), text: "if" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(2, 2 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::Else), span: Span::new(3, 3 + "else".len()), text: "else" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(7, 7 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::While), span: Span::new(8, 8 + "while".len()), text: "while" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(13, 13 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::For), span: Span::new(14, 14 + "for".len()), text: "for" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(17, 17 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::Return), span: Span::new(18, 18 + "return".len()), text: "return" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(24, 24 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::Break), span: Span::new(25, 25 + "break".len()), text: "break" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(30, 30 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::Continue), span: Span::new(31, 31 + "continue".len()), text: "continue" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_in_keyword() {
+        let text = "for x in xs";
+        let kinds = Lexer::new(text).map(|token| token.kind()).collect::<Vec<_>>();
+        assert_eq!(kinds[4], TokenKind::Keyword(KeywordKind::In));
+        assert_eq!(Lexer::new("inside").next().map(|token| token.kind()), Some(TokenKind::Identifier));
+    }
+
+    #[test]
+    fn test_literal_keyword() {
+        let text = "true false none";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Keyword(KeywordKind::True), span: Span::new(0, "true".len()), text: "true" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(4, 4 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::False), span: Span::new(5, 5 + "false".len()), text: "false" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(10, 10 + " ".len()), text: " " },
+                Token { kind: TokenKind::Keyword(KeywordKind::None), span: Span::new(11, 11 + "none".len()), text: "none" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keyword_prefix_is_identifier() {
+        let text = "iffy format";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Identifier, span: Span::new(0, "iffy".len()), text: "iffy" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(4, 4 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(5, 5 + "format".len()), text: "format" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
             ]
         );
     }
@@ -311,27 +836,135 @@ mod tests {
         assert_eq!(
             lexer.collect::<Vec<_>>(),
             vec![
-                Token { kind: TokenKind::Comma, span: Span { text, start_offset: 0, length: ",".len() } },
-                Token { kind: TokenKind::Colon, span: Span { text, start_offset: 1, length: ":".len() } },
-                Token { kind: TokenKind::Semicolon, span: Span { text, start_offset: 2, length: ";".len() } },
-                Token { kind: TokenKind::Equals, span: Span { text, start_offset: 3, length: "=".len() } },
-                Token { kind: TokenKind::Minus, span: Span { text, start_offset: 4, length: "-".len() } },
-                Token { kind: TokenKind::GreaterThan, span: Span { text, start_offset: 5, length: ">".len() } },
+                Token { kind: TokenKind::Comma, span: Span::new(0, ",".len()), text: "," },
+                Token { kind: TokenKind::Colon, span: Span::new(1, 1 + ":".len()), text: ":" },
+                Token { kind: TokenKind::Semicolon, span: Span::new(2, 2 + ";".len()), text: ";" },
+                Token { kind: TokenKind::Equals, span: Span::new(3, 3 + "=".len()), text: "=" },
+                Token { kind: TokenKind::Minus, span: Span::new(4, 4 + "-".len()), text: "-" },
+                Token { kind: TokenKind::GreaterThan, span: Span::new(5, 5 + ">".len()), text: ">" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
             ]
         );
     }
 
+    #[test]
+    fn test_operator() {
+        let text = "+-*/%!<>";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Plus, span: Span::new(0, "+".len()), text: "+" },
+                Token { kind: TokenKind::Minus, span: Span::new(1, 1 + "-".len()), text: "-" },
+                Token { kind: TokenKind::Asterisk, span: Span::new(2, 2 + "*".len()), text: "*" },
+                Token { kind: TokenKind::Slash, span: Span::new(3, 3 + "/".len()), text: "/" },
+                Token { kind: TokenKind::Percent, span: Span::new(4, 4 + "%".len()), text: "%" },
+                Token { kind: TokenKind::Exclamation, span: Span::new(5, 5 + "!".len()), text: "!" },
+                Token { kind: TokenKind::LessThan, span: Span::new(6, 6 + "<".len()), text: "<" },
+                Token { kind: TokenKind::GreaterThan, span: Span::new(7, 7 + ">".len()), text: ">" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_logical_operator() {
+        let text = "!a && b || c";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Exclamation, span: Span::new(0, "!".len()), text: "!" },
+                Token { kind: TokenKind::Identifier, span: Span::new(1, 1 + "a".len()), text: "a" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(2, 2 + " ".len()), text: " " },
+                Token { kind: TokenKind::Ampersand, span: Span::new(3, 3 + "&".len()), text: "&" },
+                Token { kind: TokenKind::Ampersand, span: Span::new(4, 4 + "&".len()), text: "&" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(5, 5 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(6, 6 + "b".len()), text: "b" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(7, 7 + " ".len()), text: " " },
+                Token { kind: TokenKind::Pipe, span: Span::new(8, 8 + "|".len()), text: "|" },
+                Token { kind: TokenKind::Pipe, span: Span::new(9, 9 + "|".len()), text: "|" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(10, 10 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(11, 11 + "c".len()), text: "c" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_kind_logical_operator() {
+        let text = "&&||";
+        let mut lexer = Lexer::new(text);
+        assert_eq!(lexer.next_kind(TokenKind::DoublePipe), None);
+        assert_eq!(lexer.next_kind(TokenKind::DoubleAmpersand), Some(Token { kind: TokenKind::DoubleAmpersand, span: Span::new(0, "&&".len()), text: "&&" }));
+        assert_eq!(lexer.next_kind(TokenKind::DoublePipe), Some(Token { kind: TokenKind::DoublePipe, span: Span::new(2, 2 + "||".len()), text: "||" }));
+        assert_eq!(lexer.next(), Some(Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" }));
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn test_bitwise_operator() {
+        let text = "&|^~<<>>";
+        let lexer = Lexer::new(text);
+        // Shift operators are not combined by the lexer.
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Ampersand, span: Span::new(0, "&".len()), text: "&" },
+                Token { kind: TokenKind::Pipe, span: Span::new(1, 1 + "|".len()), text: "|" },
+                Token { kind: TokenKind::Caret, span: Span::new(2, 2 + "^".len()), text: "^" },
+                Token { kind: TokenKind::Tilde, span: Span::new(3, 3 + "~".len()), text: "~" },
+                Token { kind: TokenKind::LessThan, span: Span::new(4, 4 + "<".len()), text: "<" },
+                Token { kind: TokenKind::LessThan, span: Span::new(5, 5 + "<".len()), text: "<" },
+                Token { kind: TokenKind::GreaterThan, span: Span::new(6, 6 + ">".len()), text: ">" },
+                Token { kind: TokenKind::GreaterThan, span: Span::new(7, 7 + ">".len()), text: ">" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_peek_kind_shift_operator() {
+        let text = "<<>>";
+        let mut lexer = Lexer::new(text);
+        assert_eq!(lexer.peek_kind(TokenKind::LeftShift), Some(Token { kind: TokenKind::LeftShift, span: Span::new(0, "<<".len()), text: "<<" }));
+        assert_eq!(lexer.peek_kind_at_offset(TokenKind::RightShift, 1), None);
+        assert_eq!(lexer.peek_kind_at_offset(TokenKind::RightShift, 2), Some(Token { kind: TokenKind::RightShift, span: Span::new(2, 2 + ">>".len()), text: ">>" }));
+        assert_eq!(lexer.peek(), Some(Token { kind: TokenKind::LessThan, span: Span::new(0, "<".len()), text: "<" }));
+    }
+
     #[test]
     fn test_delimiter() {
-        let text = "{}()";
+        let text = "{}()[]";
         let lexer = Lexer::new(text);
         assert_eq!(
             lexer.collect::<Vec<_>>(),
             vec![
-                Token { kind: TokenKind::LeftBrace, span: Span { text, start_offset: 0, length: "{".len() } },
-                Token { kind: TokenKind::RightBrace, span: Span { text, start_offset: 1, length: "}".len() } },
-                Token { kind: TokenKind::LeftParentheses, span: Span { text, start_offset: 2, length: "(".len() } },
-                Token { kind: TokenKind::RightParentheses, span: Span { text, start_offset: 3, length: ")".len() } },
+                Token { kind: TokenKind::LeftBrace, span: Span::new(0, "{".len()), text: "{" },
+                Token { kind: TokenKind::RightBrace, span: Span::new(1, 1 + "}".len()), text: "}" },
+                Token { kind: TokenKind::LeftParentheses, span: Span::new(2, 2 + "(".len()), text: "(" },
+                Token { kind: TokenKind::RightParentheses, span: Span::new(3, 3 + ")".len()), text: ")" },
+                Token { kind: TokenKind::LeftBracket, span: Span::new(4, 4 + "[".len()), text: "[" },
+                Token { kind: TokenKind::RightBracket, span: Span::new(5, 5 + "]".len()), text: "]" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reserved_punctuation() {
+        let text = "?@#$\\`";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Question, span: Span::new(0, "?".len()), text: "?" },
+                Token { kind: TokenKind::At, span: Span::new(1, 1 + "@".len()), text: "@" },
+                Token { kind: TokenKind::Hash, span: Span::new(2, 2 + "#".len()), text: "#" },
+                Token { kind: TokenKind::Dollar, span: Span::new(3, 3 + "$".len()), text: "$" },
+                Token { kind: TokenKind::Backslash, span: Span::new(4, 4 + "\\".len()), text: "\\" },
+                Token { kind: TokenKind::Backtick, span: Span::new(5, 5 + "`".len()), text: "`" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
             ]
         );
     }
@@ -343,36 +976,62 @@ mod tests {
         assert_eq!(
             lexer.collect::<Vec<_>>(),
             vec![
-                Token { kind: TokenKind::Keyword(KeywordKind::Function), span: Span { text, start_offset: 0, length: "function".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 8, length: " ".len() } },
-                Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 9, length: "foo".len() } },
-                Token { kind: TokenKind::LeftParentheses, span: Span { text, start_offset: 12, length: "(".len() } },
-                Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 13, length: "x".len() } },
-                Token { kind: TokenKind::Colon, span: Span { text, start_offset: 14, length: ":".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 15, length: " ".len() } },
-                Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 16, length: "Integer".len() } },
-                Token { kind: TokenKind::RightParentheses, span: Span { text, start_offset: 23, length: ")".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 24, length: " ".len() } },
-                Token { kind: TokenKind::Minus, span: Span { text, start_offset: 25, length: "-".len() } },
-                Token { kind: TokenKind::GreaterThan, span: Span { text, start_offset: 26, length: ">".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 27, length: " ".len() } },
-                Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 28, length: "Integer".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 35, length: " ".len() } },
-                Token { kind: TokenKind::LeftBrace, span: Span { text, start_offset: 36, length: "{".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 37, length: " ".len() } },
-                Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 38, length: "x".len() } },
-                Token { kind: TokenKind::Whitespace, span: Span { text, start_offset: 39, length: " ".len() } },
-                Token { kind: TokenKind::RightBrace, span: Span { text, start_offset: 40, length: "}".len() } },
+                Token { kind: TokenKind::Keyword(KeywordKind::Function), span: Span::new(0, "function".len()), text: "function" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(8, 8 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(9, 9 + "foo".len()), text: "foo" },
+                Token { kind: TokenKind::LeftParentheses, span: Span::new(12, 12 + "(".len()), text: "(" },
+                Token { kind: TokenKind::Identifier, span: Span::new(13, 13 + "x".len()), text: "x" },
+                Token { kind: TokenKind::Colon, span: Span::new(14, 14 + ":".len()), text: ":" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(15, 15 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(16, 16 + "Integer".len()), text: "Integer" },
+                Token { kind: TokenKind::RightParentheses, span: Span::new(23, 23 + ")".len()), text: ")" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(24, 24 + " ".len()), text: " " },
+                Token { kind: TokenKind::Minus, span: Span::new(25, 25 + "-".len()), text: "-" },
+                Token { kind: TokenKind::GreaterThan, span: Span::new(26, 26 + ">".len()), text: ">" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(27, 27 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(28, 28 + "Integer".len()), text: "Integer" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(35, 35 + " ".len()), text: " " },
+                Token { kind: TokenKind::LeftBrace, span: Span::new(36, 36 + "{".len()), text: "{" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(37, 37 + " ".len()), text: " " },
+                Token { kind: TokenKind::Identifier, span: Span::new(38, 38 + "x".len()), text: "x" },
+                Token { kind: TokenKind::Whitespace, span: Span::new(39, 39 + " ".len()), text: " " },
+                Token { kind: TokenKind::RightBrace, span: Span::new(40, 40 + "}".len()), text: "}" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
             ]
         );
     }
 
+    #[test]
+    fn test_significant() {
+        let text = "#!zinc\nfoo ( 1 )";
+        let lexer = Lexer::new(text);
+        assert_eq!(
+            lexer.significant().collect::<Vec<_>>(),
+            vec![
+                Token { kind: TokenKind::Identifier, span: Span::new(7, 7 + "foo".len()), text: "foo" },
+                Token { kind: TokenKind::LeftParentheses, span: Span::new(11, 11 + "(".len()), text: "(" },
+                Token { kind: TokenKind::Integer, span: Span::new(13, 13 + "1".len()), text: "1" },
+                Token { kind: TokenKind::RightParentheses, span: Span::new(15, 15 + ")".len()), text: ")" },
+                Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_significant_only_trivia() {
+        let text = " \n\t";
+        let mut tokens = Lexer::new(text).significant();
+        assert_eq!(tokens.next(), Some(Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" }));
+        assert_eq!(tokens.next(), None);
+    }
+
     #[test]
     fn test_next_kind_right_arrow() {
         let text = "->";
         let mut lexer = Lexer::new(text);
         let result = lexer.next_kind(TokenKind::RightArrow);
-        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span { text, start_offset: 0, length: "->".len() } }));
+        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span::new(0, "->".len()), text: "->" }));
+        assert_eq!(lexer.next(), Some(Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" }));
         assert_eq!(lexer.next(), None);
     }
 
@@ -381,17 +1040,62 @@ mod tests {
         let text = "::";
         let mut lexer = Lexer::new(text);
         let result = lexer.next_kind(TokenKind::PathSeparator);
-        assert_eq!(result, Some(Token { kind: TokenKind::PathSeparator, span: Span { text, start_offset: 0, length: "::".len() } }));
+        assert_eq!(result, Some(Token { kind: TokenKind::PathSeparator, span: Span::new(0, "::".len()), text: "::" }));
+        assert_eq!(lexer.next(), Some(Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" }));
         assert_eq!(lexer.next(), None);
     }
 
+    #[test]
+    fn test_next_kind_comparison() {
+        let text = "a <= b";
+        let mut lexer = Lexer::new(text);
+        assert_eq!(lexer.next_kind(TokenKind::LessThanOrEquals), None);
+        assert_eq!(lexer.peek_kind_at_offset(TokenKind::LessThanOrEquals, 2), Some(Token { kind: TokenKind::LessThanOrEquals, span: Span::new(2, 2 + "<=".len()), text: "<=" }));
+        lexer.next();
+        lexer.next();
+        assert_eq!(lexer.next_kind(TokenKind::LessThanOrEquals), Some(Token { kind: TokenKind::LessThanOrEquals, span: Span::new(2, 2 + "<=".len()), text: "<=" }));
+        assert_eq!(lexer.next(), Some(Token { kind: TokenKind::Whitespace, span: Span::new(4, 4 + " ".len()), text: " " }));
+    }
+
     #[test]
     fn test_next_kind_fails_when_not_matching() {
         let text = "-;";
         let mut lexer = Lexer::new(text);
         let result = lexer.next_kind(TokenKind::RightArrow);
         assert_eq!(result, None);
-        assert_eq!(lexer.next(), Some(Token { kind: TokenKind::Minus, span: Span { text, start_offset: 0, length: "-".len() } }));
+        assert_eq!(lexer.next(), Some(Token { kind: TokenKind::Minus, span: Span::new(0, "-".len()), text: "-" }));
+    }
+
+    #[test]
+    fn test_next_kind_after_peeking_ahead() {
+        let text = "->foo";
+        let mut lexer = Lexer::new(text);
+        assert_eq!(lexer.peek_at_offset(2), Some(Token { kind: TokenKind::Identifier, span: Span::new(2, 2 + "foo".len()), text: "foo" }));
+        let result = lexer.next_kind(TokenKind::RightArrow);
+        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span::new(0, "->".len()), text: "->" }));
+        assert_eq!(lexer.next(), Some(Token { kind: TokenKind::Identifier, span: Span::new(2, 2 + "foo".len()), text: "foo" }));
+        assert_eq!(lexer.next(), Some(Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" }));
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn test_next_kind_fails_on_incomplete_input() {
+        let text = ":";
+        let mut lexer = Lexer::new(text);
+        let result = lexer.next_kind(TokenKind::PathSeparator);
+        assert_eq!(result, None);
+        assert_eq!(lexer.next(), Some(Token { kind: TokenKind::Colon, span: Span::new(0, ":".len()), text: ":" }));
+        assert_eq!(lexer.next(), Some(Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" }));
+        assert_eq!(lexer.next(), None);
+    }
+
+    #[test]
+    fn test_next_kind_contextual_keyword() {
+        let text = "mutable mutable";
+        let mut lexer = Lexer::new(text);
+        assert_eq!(lexer.next_kind(TokenKind::Keyword(KeywordKind::Mutable)), Some(Token { kind: TokenKind::Keyword(KeywordKind::Mutable), span: Span::new(0, "mutable".len()), text: "mutable" }));
+        assert_eq!(lexer.next_kind(TokenKind::Whitespace), Some(Token { kind: TokenKind::Whitespace, span: Span::new(7, 7 + " ".len()), text: " " }));
+        assert_eq!(lexer.next_kind(TokenKind::Identifier), Some(Token { kind: TokenKind::Identifier, span: Span::new(8, 8 + "mutable".len()), text: "mutable" }));
     }
 
     #[test]
@@ -399,8 +1103,8 @@ mod tests {
         let text = "->";
         let mut lexer = Lexer::new(text);
         let result = lexer.peek_kind(TokenKind::RightArrow);
-        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span { text, start_offset: 0, length: "->".len() } }));
-        assert_eq!(lexer.peek(), Some(Token { kind: TokenKind::Minus, span: Span { text, start_offset: 0, length: "-".len() } }));
+        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span::new(0, "->".len()), text: "->" }));
+        assert_eq!(lexer.peek(), Some(Token { kind: TokenKind::Minus, span: Span::new(0, "-".len()), text: "-" }));
     }
 
     #[test]
@@ -408,8 +1112,8 @@ mod tests {
         let text = "::";
         let mut lexer = Lexer::new(text);
         let result = lexer.peek_kind(TokenKind::PathSeparator);
-        assert_eq!(result, Some(Token { kind: TokenKind::PathSeparator, span: Span { text, start_offset: 0, length: "::".len() } }));
-        assert_eq!(lexer.peek(), Some(Token { kind: TokenKind::Colon, span: Span { text, start_offset: 0, length: ":".len() } }));
+        assert_eq!(result, Some(Token { kind: TokenKind::PathSeparator, span: Span::new(0, "::".len()), text: "::" }));
+        assert_eq!(lexer.peek(), Some(Token { kind: TokenKind::Colon, span: Span::new(0, ":".len()), text: ":" }));
     }
 
     #[test]
@@ -425,8 +1129,8 @@ mod tests {
         let text = "foo ->";
         let mut lexer = Lexer::new(text);
         let result = lexer.peek_kind_at_offset(TokenKind::RightArrow, 2);
-        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span { text, start_offset: 4, length: "->".len() } }));
-        assert_eq!(lexer.peek(), Some(Token { kind: TokenKind::Identifier, span: Span { text, start_offset: 0, length: "foo".len() } }));
+        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span::new(4, 4 + "->".len()), text: "->" }));
+        assert_eq!(lexer.peek(), Some(Token { kind: TokenKind::Identifier, span: Span::new(0, "foo".len()), text: "foo" }));
     }
 
     #[test]
@@ -434,7 +1138,7 @@ mod tests {
         let text = "foo ::";
         let mut lexer = Lexer::new(text);
         let result = lexer.peek_kind_at_offset(TokenKind::PathSeparator, 2);
-        assert_eq!(result, Some(Token { kind: TokenKind::PathSeparator, span: Span { text, start_offset: 4, length: "::".len() } }));
+        assert_eq!(result, Some(Token { kind: TokenKind::PathSeparator, span: Span::new(4, 4 + "::".len()), text: "::" }));
     }
 
     #[test]
@@ -442,7 +1146,7 @@ mod tests {
         let text = "->";
         let mut lexer = Lexer::new(text);
         let result = lexer.peek_kind_at_offset(TokenKind::RightArrow, 0);
-        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span { text, start_offset: 0, length: "->".len() } }));
+        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span::new(0, "->".len()), text: "->" }));
     }
 
     #[test]
@@ -452,4 +1156,35 @@ mod tests {
         let result = lexer.peek_kind_at_offset(TokenKind::RightArrow, 2);
         assert_eq!(result, None);
     }
+
+    /// The tokens cover the source code without gaps or overlaps and the last token is the only
+    /// end of file token.
+    fn assert_lossless(text: &str, tokens: &[Token]) {
+        let (last, tokens) = tokens.split_last().expect("no end of file token");
+        assert_eq!(*last, Token { kind: TokenKind::EndOfFile, span: Span::empty(text.len()), text: "" });
+        let mut offset = 0;
+        for token in tokens {
+            assert_ne!(token.kind, TokenKind::EndOfFile);
+            assert!(!token.span.is_empty(), "empty token {token:?}");
+            assert_eq!(token.span.start(), offset);
+            assert_eq!(token.text, token.span.text(text));
+            offset = token.span.end();
+        }
+        assert_eq!(offset, text.len());
+        assert_eq!(tokens.iter().map(|token| token.text).collect::<String>(), text);
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_lossless(text in proptest::prelude::any::<String>()) {
+            assert_lossless(&text, &Lexer::new(&text).collect::<Vec<_>>());
+            assert_lossless(&text, &Lexer::with_grapheme_clusters(&text).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn test_lossless_source_like(text in "(r|#|!|\\[|\"|[a-z0-9_]|[ \n]|[-+<>=:;{}()]|\u{301}|\u{200D}|\u{1F1E6}|\u{1F468}|§)*") {
+            assert_lossless(&text, &Lexer::new(&text).collect::<Vec<_>>());
+            assert_lossless(&text, &Lexer::with_grapheme_clusters(&text).collect::<Vec<_>>());
+        }
+    }
 }
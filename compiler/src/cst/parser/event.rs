@@ -0,0 +1,165 @@
+//! Events emitted by the parser, and the tree builder which turns them into a green tree.
+//!
+//! The grammar only describes the structure of the source code as a flat list of events. Keeping
+//! the construction of the tree separate lets the events be inspected or rearranged before the
+//! tree is built.
+
+use std::mem;
+use crate::cst::TokenKind;
+use crate::cst::tree::{GreenElement, GreenNode, GreenToken, SyntaxKind};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Event<'text> {
+    /// Start a new tree inside the current tree.
+    ///
+    /// A tree which was started after its first child was already parsed is not placed before
+    /// that child in the list of events. Instead, the start of the first child points forward to
+    /// it, so that starting a tree around a child does not move the events of that child.
+    Start {
+        kind: SyntaxKind,
+        forward_parent: Option<usize>,
+    },
+    /// Finish the current tree.
+    Finish,
+    /// Add a token to the current tree.
+    Token {
+        kind: TokenKind,
+        text: &'text str,
+    },
+    /// An event which has been replaced, and is ignored.
+    Tombstone,
+}
+
+/// Build a green tree from a list of events, which must describe exactly one tree.
+pub(super) fn build(mut events: Vec<Event>) -> GreenNode {
+    let mut builder = TreeBuilder::default();
+    let mut kinds = Vec::new();
+    for index in 0..events.len() {
+        match mem::replace(&mut events[index], Event::Tombstone) {
+            Event::Start { kind, mut forward_parent } => {
+                kinds.push(kind);
+                while let Some(parent) = forward_parent {
+                    let Event::Start { kind, forward_parent: next } = mem::replace(&mut events[parent], Event::Tombstone) else {
+                        unreachable!("forward parent is not a start event");
+                    };
+                    kinds.push(kind);
+                    forward_parent = next;
+                }
+                for kind in kinds.drain(..).rev() {
+                    builder.start(kind);
+                }
+            }
+            Event::Finish => builder.finish(),
+            Event::Token { kind, text } => builder.token(kind, text),
+            Event::Tombstone => {}
+        }
+    }
+    builder.into_tree()
+}
+
+/// Builds up a green tree from the bottom up.
+#[derive(Debug, Default)]
+pub(super) struct TreeBuilder {
+    /// The kind and children of the trees which have been started but not finished yet,
+    /// innermost last.
+    stack: Vec<(SyntaxKind, Vec<GreenElement>)>,
+    /// The outermost tree, once it is finished.
+    root: Option<GreenNode>,
+}
+
+impl TreeBuilder {
+    pub(super) fn start(&mut self, kind: SyntaxKind) {
+        self.stack.push((kind, Vec::new()));
+    }
+
+    pub(super) fn finish(&mut self) {
+        let (kind, children) = self.stack.pop().expect("no tree to finish");
+        let tree = GreenNode::new(kind, children);
+        match self.stack.last_mut() {
+            Some((_, parent)) => parent.push(GreenElement::Node(tree)),
+            None => self.root = Some(tree),
+        }
+    }
+
+    pub(super) fn token(&mut self, kind: TokenKind, text: &str) {
+        let (_, children) = self.stack.last_mut().expect("no tree started");
+        children.push(GreenElement::Token(GreenToken::new(kind, text)));
+    }
+
+    pub(super) fn into_tree(self) -> GreenNode {
+        debug_assert!(self.stack.is_empty(), "unfinished tree");
+        self.root.expect("no tree built")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(kind: SyntaxKind) -> Event<'static> {
+        Event::Start { kind, forward_parent: None }
+    }
+
+    fn token(text: &str) -> Event<'_> {
+        Event::Token { kind: TokenKind::Identifier, text }
+    }
+
+    #[test]
+    fn test_build() {
+        let tree = build(vec![
+            start(SyntaxKind::File),
+            start(SyntaxKind::PathExpression),
+            token("a"),
+            Event::Finish,
+            token("b"),
+            Event::Finish,
+        ]);
+        assert_eq!(tree, GreenNode::new(SyntaxKind::File, vec![
+            GreenElement::Node(GreenNode::new(SyntaxKind::PathExpression, vec![
+                GreenElement::Token(GreenToken::new(TokenKind::Identifier, "a")),
+            ])),
+            GreenElement::Token(GreenToken::new(TokenKind::Identifier, "b")),
+        ]));
+    }
+
+    #[test]
+    fn test_forward_parent() {
+        // `a` is parsed first, then wrapped in a call, which is then wrapped in a statement.
+        let tree = build(vec![
+            start(SyntaxKind::Statement),
+            Event::Start { kind: SyntaxKind::PathExpression, forward_parent: Some(4) },
+            token("a"),
+            Event::Finish,
+            Event::Start { kind: SyntaxKind::CallExpression, forward_parent: None },
+            token("b"),
+            Event::Finish,
+            Event::Finish,
+        ]);
+        let call = GreenNode::new(SyntaxKind::CallExpression, vec![
+            GreenElement::Node(GreenNode::new(SyntaxKind::PathExpression, vec![
+                GreenElement::Token(GreenToken::new(TokenKind::Identifier, "a")),
+            ])),
+            GreenElement::Token(GreenToken::new(TokenKind::Identifier, "b")),
+        ]);
+        assert_eq!(tree, GreenNode::new(SyntaxKind::Statement, vec![GreenElement::Node(call)]));
+    }
+
+    #[test]
+    fn test_forward_parent_chain() {
+        let tree = build(vec![
+            Event::Start { kind: SyntaxKind::LiteralExpression, forward_parent: Some(3) },
+            token("1"),
+            Event::Finish,
+            Event::Start { kind: SyntaxKind::BinaryExpression, forward_parent: Some(5) },
+            Event::Finish,
+            start(SyntaxKind::Statement),
+            Event::Finish,
+        ]);
+        assert_eq!(tree.kind(), SyntaxKind::Statement);
+        let GreenElement::Node(binary) = &tree.children()[0] else {
+            panic!("expected a node");
+        };
+        assert_eq!(binary.kind(), SyntaxKind::BinaryExpression);
+        assert!(matches!(&binary.children()[0], GreenElement::Node(literal) if literal.kind() == SyntaxKind::LiteralExpression));
+    }
+}
@@ -0,0 +1,1038 @@
+//! Grammar of expressions and types.
+
+use super::{ParseErrorKind, Parser};
+use super::items::field;
+use super::patterns::{at_pattern, pattern};
+use crate::cst::{KeywordKind, TokenKind};
+use crate::cst::tree::SyntaxKind;
+
+/// How a chain of binary operators of the same precedence is grouped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a = b = c` is `a = (b = c)`.
+    Right,
+}
+
+/// Returns the precedence and the associativity of a binary operator, or `None` if the token is
+/// not a binary operator.
+///
+/// An operator with a higher precedence binds tighter, so `a + b * c` is `a + (b * c)`. Combined
+/// operators, such as `==`, have to be passed as their combined kind.
+pub fn precedence(kind: TokenKind) -> Option<(u8, Associativity)> {
+    BINARY_OPERATORS.iter()
+        .find(|&&(operator, ..)| operator == kind)
+        .map(|&(_, precedence, associativity)| (precedence, associativity))
+}
+
+/// The binary operators with their precedence and associativity, in the order the parser tries
+/// them. Combined operators come before the operators they are made of, so that `==` is not taken
+/// for `=`.
+const BINARY_OPERATORS: &[(TokenKind, u8, Associativity)] = &[
+    (TokenKind::DoubleEquals, 3, Associativity::Left),
+    (TokenKind::NotEquals, 3, Associativity::Left),
+    (TokenKind::LessThanOrEquals, 3, Associativity::Left),
+    (TokenKind::GreaterThanOrEquals, 3, Associativity::Left),
+    (TokenKind::DoublePipe, 1, Associativity::Left),
+    (TokenKind::DoubleAmpersand, 2, Associativity::Left),
+    (TokenKind::LeftShift, 7, Associativity::Left),
+    (TokenKind::RightShift, 7, Associativity::Left),
+    (TokenKind::Equals, 0, Associativity::Right),
+    (TokenKind::LessThan, 3, Associativity::Left),
+    (TokenKind::GreaterThan, 3, Associativity::Left),
+    (TokenKind::Pipe, 4, Associativity::Left),
+    (TokenKind::Caret, 5, Associativity::Left),
+    (TokenKind::Ampersand, 6, Associativity::Left),
+    (TokenKind::Plus, 8, Associativity::Left),
+    (TokenKind::Minus, 8, Associativity::Left),
+    (TokenKind::Asterisk, 9, Associativity::Left),
+    (TokenKind::Slash, 9, Associativity::Left),
+    (TokenKind::Percent, 9, Associativity::Left),
+];
+
+/// The binding power of the operand of a prefix operator, which is higher than the binding power
+/// of any binary operator.
+const PREFIX_POWER: u8 = 21;
+
+/// Returns the left and right binding power of a binary operator.
+///
+/// An operator binds tighter than another if its binding power is higher. An operator is left
+/// associative if its right binding power is higher than its left, and right associative
+/// otherwise.
+fn binding_power(precedence: u8, associativity: Associativity) -> (u8, u8) {
+    let power = 2 * precedence + 1;
+    match associativity {
+        Associativity::Left => (power, power + 1),
+        Associativity::Right => (power + 1, power),
+    }
+}
+
+/// Parse an expression, or report an error without consuming anything if there is none.
+pub(super) fn expression(p: &mut Parser) {
+    expression_with_power(p, 0);
+}
+
+/// Parse an expression which only contains binary operators binding at least as tight as the
+/// given binding power.
+fn expression_with_power(p: &mut Parser, minimum: u8) {
+    let checkpoint = p.checkpoint();
+    if is_prefix_operator(p.peek()) {
+        p.start(SyntaxKind::PrefixExpression);
+        p.bump();
+        expression_with_power(p, PREFIX_POWER);
+        p.finish();
+    } else if primary_expression(p) {
+        while p.at(TokenKind::LeftParentheses) {
+            p.start_at(checkpoint, SyntaxKind::CallExpression);
+            arguments(p);
+            p.finish();
+        }
+    } else {
+        return;
+    }
+    while let Some(&(operator, precedence, associativity)) = BINARY_OPERATORS.iter()
+        .find(|&&(operator, ..)| p.at(operator)) {
+        let (left, right) = binding_power(precedence, associativity);
+        if left < minimum {
+            break;
+        }
+        p.start_at(checkpoint, SyntaxKind::BinaryExpression);
+        p.eat(operator);
+        expression_with_power(p, right);
+        p.finish();
+    }
+}
+
+/// A function parsing a construct of the grammar.
+type Rule = fn(&mut Parser);
+
+/// The tokens an expression without operators starts with, and the function parsing the
+/// expression. [`primary_expression`] and [`at_expression`] both read this table, so they agree
+/// on where an expression starts.
+const PRIMARY_EXPRESSIONS: &[(TokenKind, Rule)] = &[
+    (TokenKind::Integer, literal_expression),
+    (TokenKind::RawString, literal_expression),
+    (TokenKind::Keyword(KeywordKind::True), literal_expression),
+    (TokenKind::Keyword(KeywordKind::False), literal_expression),
+    (TokenKind::Keyword(KeywordKind::None), literal_expression),
+    (TokenKind::Identifier, path_expression),
+    (TokenKind::LeftParentheses, parenthesized_expression),
+    (TokenKind::LeftBrace, block_expression),
+    (TokenKind::Keyword(KeywordKind::If), if_expression),
+    (TokenKind::Keyword(KeywordKind::For), for_loop),
+    (TokenKind::Keyword(KeywordKind::Match), match_expression),
+];
+
+/// Parse an expression without operators, or report an error if there is none.
+///
+/// Returns whether an expression was parsed.
+fn primary_expression(p: &mut Parser) -> bool {
+    let Some(&(_, parse)) = PRIMARY_EXPRESSIONS.iter().find(|&&(kind, _)| p.at(kind)) else {
+        p.error(ParseErrorKind::ExpectedExpression);
+        return false;
+    };
+    parse(p);
+    true
+}
+
+/// Check if the next token starts an expression.
+fn at_expression(p: &mut Parser) -> bool {
+    is_prefix_operator(p.peek()) || PRIMARY_EXPRESSIONS.iter().any(|&(kind, _)| p.at(kind))
+}
+
+/// `-`, `!`, `~`, and `&` (dereference)
+fn is_prefix_operator(kind: TokenKind) -> bool {
+    matches!(kind, TokenKind::Minus | TokenKind::Exclamation | TokenKind::Tilde | TokenKind::Ampersand)
+}
+
+/// `1`, `true`, `none`
+fn literal_expression(p: &mut Parser) {
+    p.start(SyntaxKind::LiteralExpression);
+    p.bump();
+    p.finish();
+}
+
+/// `foo` or `math::Integer::parse`
+///
+/// The segments of a path are identifier tokens, separated by a combined `::` token.
+pub(super) fn path_expression(p: &mut Parser) {
+    p.start(SyntaxKind::PathExpression);
+    p.bump();
+    while p.eat(TokenKind::PathSeparator) {
+        p.expect(TokenKind::Identifier);
+    }
+    p.finish();
+}
+
+/// `(a + b)`
+///
+/// An unclosed parenthesis is reported right after the expression, and the expression ends there.
+fn parenthesized_expression(p: &mut Parser) {
+    p.start(SyntaxKind::ParenthesizedExpression);
+    p.open(TokenKind::LeftParentheses);
+    expression(p);
+    p.close();
+    p.finish();
+}
+
+/// The tokens at which parsing continues after a malformed argument.
+const ARGUMENT_RECOVERY: &[TokenKind] = &[
+    TokenKind::Comma,
+    TokenKind::RightParentheses,
+];
+
+/// `(a, b + 1,)`
+///
+/// The tokens after a malformed argument are skipped up to the next comma, so the arguments after
+/// it are still parsed.
+pub(super) fn arguments(p: &mut Parser) {
+    p.start(SyntaxKind::Arguments);
+    p.open(TokenKind::LeftParentheses);
+    while !p.at_close() && !p.at_end() {
+        expression(p);
+        if !p.at_any(ARGUMENT_RECOVERY) && !p.at_close() && !p.at_end() {
+            p.error_until(ParseErrorKind::Expected(TokenKind::Comma), ARGUMENT_RECOVERY);
+        }
+        if !p.eat(TokenKind::Comma) {
+            break;
+        }
+    }
+    p.close();
+    p.finish();
+}
+
+/// The keywords which start an item, but not a statement.
+const ITEM_KEYWORDS: &[TokenKind] = &[
+    TokenKind::Keyword(KeywordKind::Module),
+    TokenKind::Keyword(KeywordKind::Class),
+    TokenKind::Keyword(KeywordKind::Enum),
+    TokenKind::Keyword(KeywordKind::Struct),
+    TokenKind::Keyword(KeywordKind::Function),
+];
+
+/// `{ let x: Integer = 1; { foo; } bar }`
+///
+/// The last expression is not followed by a semicolon if the block yields its value. An unclosed
+/// block ends before a keyword which can only start an item, so that the items after it are not
+/// parsed as statements.
+pub(super) fn block_expression(p: &mut Parser) {
+    p.start(SyntaxKind::BlockExpression);
+    p.open(TokenKind::LeftBrace);
+    while !p.at_close() && !p.at_end() && !p.at_any(ITEM_KEYWORDS) {
+        statement(p);
+    }
+    p.close();
+    p.finish();
+}
+
+/// The tokens at which parsing continues after the tokens before the block of an `if`, an `else`
+/// or a loop.
+const BRANCH_RECOVERY: &[TokenKind] = &[
+    TokenKind::LeftBrace,
+    TokenKind::RightBrace,
+    TokenKind::Semicolon,
+    TokenKind::Keyword(KeywordKind::Else),
+];
+
+/// `if a { ... } else if b { ... } else { ... }`
+fn if_expression(p: &mut Parser) {
+    p.start(SyntaxKind::IfExpression);
+    p.bump();
+    if p.at(TokenKind::LeftBrace) {
+        p.error(ParseErrorKind::ExpectedExpression);
+    } else {
+        expression(p);
+    }
+    branch(p);
+    if p.eat(TokenKind::Keyword(KeywordKind::Else)) {
+        if p.at(TokenKind::Keyword(KeywordKind::If)) {
+            if_expression(p);
+        } else {
+            branch(p);
+        }
+    }
+    p.finish();
+}
+
+/// The block of an `if`, an `else` or a loop.
+///
+/// Unexpected tokens before the block, such as the rest of a malformed condition, are skipped.
+fn branch(p: &mut Parser) {
+    if !p.at_any(BRANCH_RECOVERY) && !p.at_end() {
+        p.error_until(ParseErrorKind::Expected(TokenKind::LeftBrace), BRANCH_RECOVERY);
+    } else if !p.at(TokenKind::LeftBrace) {
+        p.error(ParseErrorKind::Expected(TokenKind::LeftBrace));
+    }
+    if p.at(TokenKind::LeftBrace) {
+        block_expression(p);
+    }
+}
+
+/// `for item in items { ... }`
+fn for_loop(p: &mut Parser) {
+    p.start(SyntaxKind::ForLoop);
+    p.bump();
+    pattern(p);
+    p.expect(TokenKind::Keyword(KeywordKind::In));
+    if p.at(TokenKind::LeftBrace) {
+        p.error(ParseErrorKind::ExpectedExpression);
+    } else {
+        expression(p);
+    }
+    branch(p);
+    p.finish();
+}
+
+/// The tokens at which parsing continues after a malformed pattern of a match arm.
+const ARM_RECOVERY: &[TokenKind] = &[
+    TokenKind::RightArrow,
+    TokenKind::Comma,
+    TokenKind::RightBrace,
+];
+
+/// `match value { 1 -> a, x -> { b } _ -> c }`
+fn match_expression(p: &mut Parser) {
+    p.start(SyntaxKind::MatchExpression);
+    p.bump();
+    if p.at(TokenKind::LeftBrace) {
+        p.error(ParseErrorKind::ExpectedExpression);
+    } else {
+        expression(p);
+    }
+    if p.open(TokenKind::LeftBrace) {
+        while !p.at_close() && !p.at_end() {
+            match_arm(p);
+        }
+        p.close();
+    }
+    p.finish();
+}
+
+/// `1 -> a,` or `x -> { b }`
+///
+/// An arm is followed by a comma, unless its expression ends in a block or it is the last arm.
+fn match_arm(p: &mut Parser) {
+    p.start(SyntaxKind::MatchArm);
+    if at_pattern(p) {
+        pattern(p);
+    } else if !p.at_any(ARM_RECOVERY) {
+        p.error_until(ParseErrorKind::ExpectedPattern, ARM_RECOVERY);
+    } else {
+        p.error(ParseErrorKind::ExpectedPattern);
+    }
+    let mut ends_in_block = false;
+    if p.expect(TokenKind::RightArrow) {
+        ends_in_block = block_like_expression(p);
+        if !ends_in_block {
+            expression(p);
+        }
+    }
+    if !p.eat(TokenKind::Comma) && !ends_in_block && !p.at_close() {
+        p.error(ParseErrorKind::Expected(TokenKind::Comma));
+    }
+    p.finish();
+}
+
+/// Parse an expression which ends in a block, such as an `if`, if there is one.
+///
+/// Returns whether an expression was parsed.
+fn block_like_expression(p: &mut Parser) -> bool {
+    match p.peek() {
+        TokenKind::LeftBrace => block_expression(p),
+        TokenKind::Keyword(KeywordKind::If) => if_expression(p),
+        TokenKind::Keyword(KeywordKind::For) => for_loop(p),
+        TokenKind::Keyword(KeywordKind::Match) => match_expression(p),
+        _ => return false,
+    }
+    true
+}
+
+/// `let x: Integer = 1;`, `return foo;`, `foo;`, or `{ foo; }`
+///
+/// An expression which ends in a block, such as a nested block, does not need a semicolon to be a
+/// statement. It is not continued by an operator either, so `{ a } - b` is a block followed by a
+/// prefix expression. An expression without a semicolon before the closing brace is not wrapped in
+/// a statement, as it is the value of the block.
+fn statement(p: &mut Parser) {
+    if p.at(TokenKind::Keyword(KeywordKind::Field)) {
+        p.start(SyntaxKind::Statement);
+        field(p);
+        p.finish();
+        return;
+    }
+    if p.at(TokenKind::Keyword(KeywordKind::Return)) {
+        p.start(SyntaxKind::Statement);
+        return_statement(p);
+        p.finish();
+        return;
+    }
+    if !at_expression(p) {
+        p.error_and_bump(ParseErrorKind::ExpectedExpression);
+        return;
+    }
+    let checkpoint = p.checkpoint();
+    let ends_in_block = block_like_expression(p);
+    if !ends_in_block {
+        expression(p);
+    }
+    let is_value = p.at_close() || p.at_end();
+    if p.at(TokenKind::Semicolon) {
+        p.start_at(checkpoint, SyntaxKind::Statement);
+        p.bump();
+        p.finish();
+    } else if ends_in_block && !is_value {
+        p.start_at(checkpoint, SyntaxKind::Statement);
+        p.finish();
+    } else if !is_value {
+        p.error(ParseErrorKind::Expected(TokenKind::Semicolon));
+    }
+}
+
+/// `return;` or `return foo;`
+///
+/// The semicolon can be left out before the closing brace of a block. Whether the return is
+/// inside a function is checked by [`crate::cst::validation`].
+fn return_statement(p: &mut Parser) {
+    p.start(SyntaxKind::ReturnStatement);
+    p.bump();
+    if at_expression(p) {
+        expression(p);
+    }
+    if !p.at_close() {
+        p.expect(TokenKind::Semicolon);
+    }
+    p.finish();
+}
+
+/// `Integer`, `math::Integer` or `List<Integer>`
+pub(super) fn type_(p: &mut Parser) {
+    if !p.at(TokenKind::Identifier) {
+        p.error(ParseErrorKind::ExpectedType);
+        return;
+    }
+    p.start(SyntaxKind::Type);
+    path_expression(p);
+    if p.at(TokenKind::LessThan) {
+        generic_arguments(p);
+    }
+    p.finish();
+}
+
+/// `<Integer, List<Integer>>`
+///
+/// Each list only asks for a single `>`, so the `>>` closing two nested lists is never combined
+/// into a shift operator.
+fn generic_arguments(p: &mut Parser) {
+    p.start(SyntaxKind::GenericArguments);
+    p.bump();
+    while !p.at(TokenKind::GreaterThan) && !p.at_end() {
+        type_(p);
+        if !p.at(TokenKind::GreaterThan) && !p.expect(TokenKind::Comma) {
+            break;
+        }
+    }
+    p.expect(TokenKind::GreaterThan);
+    p.finish();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cst::{KeywordKind, TokenKind};
+    use crate::cst::parser::{parse, precedence, Associativity, ParseError, ParseErrorKind};
+    use crate::cst::parser::tests::render;
+    use crate::cst::syntax::SyntaxElement;
+    use crate::cst::tree::SyntaxKind;
+    use crate::span::Span;
+
+    /// Parses the expression as the initializer of a field and compares the rendered expression.
+    fn check(text: &str, expected: &str) {
+        let parse = parse(&format!("let x: T = {text};"));
+        assert_eq!(parse.errors(), []);
+        let field = parse.syntax().children_with_tokens()
+            .find_map(|child| child.as_node().cloned())
+            .unwrap();
+        assert_eq!(field.kind(), SyntaxKind::Field);
+        let expression = field.children_with_tokens()
+            .filter_map(|child| match child {
+                SyntaxElement::Node(node) if node.kind() != SyntaxKind::Type => Some(node),
+                _ => None
+            })
+            .next()
+            .unwrap();
+        let mut output = String::new();
+        render(&expression, 0, &mut output);
+        assert_eq!(output, expected.trim_start());
+    }
+
+    #[test]
+    fn test_precedence() {
+        check("1 + 2 * 3", r#"
+BinaryExpression
+  LiteralExpression
+    "1"
+  "+"
+  BinaryExpression
+    LiteralExpression
+      "2"
+    "*"
+    LiteralExpression
+      "3"
+"#);
+    }
+
+    #[test]
+    fn test_precedence_table() {
+        assert_eq!(precedence(TokenKind::Equals), Some((0, Associativity::Right)));
+        assert_eq!(precedence(TokenKind::Minus), Some((8, Associativity::Left)));
+        let level = |kind| precedence(kind).unwrap().0;
+        assert!(level(TokenKind::Asterisk) > level(TokenKind::Plus));
+        assert!(level(TokenKind::DoubleAmpersand) > level(TokenKind::DoublePipe));
+        assert_eq!(precedence(TokenKind::Identifier), None);
+        assert_eq!(precedence(TokenKind::RightArrow), None);
+        for &(_, precedence, associativity) in super::BINARY_OPERATORS {
+            let (left, right) = super::binding_power(precedence, associativity);
+            assert!(left.max(right) < super::PREFIX_POWER);
+        }
+        for (index, &(operator, ..)) in super::BINARY_OPERATORS.iter().enumerate() {
+            let parts = operator.decompose();
+            assert!(super::BINARY_OPERATORS[..index].iter().all(|(earlier, ..)| !parts.contains(earlier)));
+        }
+    }
+
+    #[test]
+    fn test_left_associative() {
+        check("a - b - c", r#"
+BinaryExpression
+  BinaryExpression
+    PathExpression
+      "a"
+    "-"
+    PathExpression
+      "b"
+  "-"
+  PathExpression
+    "c"
+"#);
+    }
+
+    #[test]
+    fn test_right_associative() {
+        check("a = b = c", r#"
+BinaryExpression
+  PathExpression
+    "a"
+  "="
+  BinaryExpression
+    PathExpression
+      "b"
+    "="
+    PathExpression
+      "c"
+"#);
+    }
+
+    #[test]
+    fn test_combined_operators() {
+        check("a == b && c <= d << 1", r#"
+BinaryExpression
+  BinaryExpression
+    PathExpression
+      "a"
+    "=="
+    PathExpression
+      "b"
+  "&&"
+  BinaryExpression
+    PathExpression
+      "c"
+    "<="
+    BinaryExpression
+      PathExpression
+        "d"
+      "<<"
+      LiteralExpression
+        "1"
+"#);
+    }
+
+    #[test]
+    fn test_prefix() {
+        check("-a * !&b", r#"
+BinaryExpression
+  PrefixExpression
+    "-"
+    PathExpression
+      "a"
+  "*"
+  PrefixExpression
+    "!"
+    PrefixExpression
+      "&"
+      PathExpression
+        "b"
+"#);
+    }
+
+    #[test]
+    fn test_parenthesized() {
+        check("(a + b) * -(c)", r#"
+BinaryExpression
+  ParenthesizedExpression
+    "("
+    BinaryExpression
+      PathExpression
+        "a"
+      "+"
+      PathExpression
+        "b"
+    ")"
+  "*"
+  PrefixExpression
+    "-"
+    ParenthesizedExpression
+      "("
+      PathExpression
+        "c"
+      ")"
+"#);
+    }
+
+    #[test]
+    fn test_unclosed_parenthesis() {
+        let parse = parse("let x: T = (a + b;\nlet y: T = 1;");
+        assert_eq!(parse.errors(), [ParseError {
+            kind: ParseErrorKind::Unclosed { close: TokenKind::RightParentheses, open: Span::new(11, 12) },
+            span: Span::empty(17),
+        }]);
+        assert_eq!(parse.syntax().children().count(), 2);
+    }
+
+    #[test]
+    fn test_if() {
+        check("{ if a { 1 } else if !b { 2 } else { 3 } }", r#"
+BlockExpression
+  "{"
+  IfExpression
+    "if"
+    PathExpression
+      "a"
+    BlockExpression
+      "{"
+      LiteralExpression
+        "1"
+      "}"
+    "else"
+    IfExpression
+      "if"
+      PrefixExpression
+        "!"
+        PathExpression
+          "b"
+      BlockExpression
+        "{"
+        LiteralExpression
+          "2"
+        "}"
+      "else"
+      BlockExpression
+        "{"
+        LiteralExpression
+          "3"
+        "}"
+  "}"
+"#);
+    }
+
+    #[test]
+    fn test_if_statement() {
+        check("{ if a { b; } c }", r#"
+BlockExpression
+  "{"
+  Statement
+    IfExpression
+      "if"
+      PathExpression
+        "a"
+      BlockExpression
+        "{"
+        Statement
+          PathExpression
+            "b"
+          ";"
+        "}"
+  PathExpression
+    "c"
+  "}"
+"#);
+    }
+
+    #[test]
+    fn test_if_missing_condition() {
+        let parse = parse("let x: T = if { 1 } else { 2 };");
+        assert_eq!(parse.errors(), [ParseError {
+            kind: ParseErrorKind::ExpectedExpression,
+            span: Span::empty(13),
+        }]);
+    }
+
+    #[test]
+    fn test_if_malformed_condition() {
+        let parse = parse("let x: T = if a b c { 1 } else 2;");
+        assert_eq!(parse.errors(), [
+            ParseError { kind: ParseErrorKind::Expected(TokenKind::LeftBrace), span: Span::new(16, 19) },
+            ParseError { kind: ParseErrorKind::Expected(TokenKind::LeftBrace), span: Span::new(31, 32) },
+        ]);
+    }
+
+    #[test]
+    fn test_for() {
+        check("{ for item in items { f(item); } }", r#"
+BlockExpression
+  "{"
+  ForLoop
+    "for"
+    BindingPattern
+      "item"
+    "in"
+    PathExpression
+      "items"
+    BlockExpression
+      "{"
+      Statement
+        CallExpression
+          PathExpression
+            "f"
+          Arguments
+            "("
+            PathExpression
+              "item"
+            ")"
+        ";"
+      "}"
+  "}"
+"#);
+    }
+
+    #[test]
+    fn test_for_missing_in() {
+        let parse = parse("let x: T = for item items {};");
+        assert_eq!(parse.errors(), [ParseError {
+            kind: ParseErrorKind::Expected(TokenKind::Keyword(KeywordKind::In)),
+            span: Span::empty(19),
+        }]);
+    }
+
+    #[test]
+    fn test_return() {
+        check("{ return; if a { return 1 } return b + 1; }", r#"
+BlockExpression
+  "{"
+  Statement
+    ReturnStatement
+      "return"
+      ";"
+  Statement
+    IfExpression
+      "if"
+      PathExpression
+        "a"
+      BlockExpression
+        "{"
+        Statement
+          ReturnStatement
+            "return"
+            LiteralExpression
+              "1"
+        "}"
+  Statement
+    ReturnStatement
+      "return"
+      BinaryExpression
+        PathExpression
+          "b"
+        "+"
+        LiteralExpression
+          "1"
+      ";"
+  "}"
+"#);
+    }
+
+    #[test]
+    fn test_match() {
+        check("match x { 1 -> a, none -> { b } _ -> c }", r#"
+MatchExpression
+  "match"
+  PathExpression
+    "x"
+  "{"
+  MatchArm
+    LiteralPattern
+      "1"
+    "->"
+    PathExpression
+      "a"
+    ","
+  MatchArm
+    LiteralPattern
+      "none"
+    "->"
+    BlockExpression
+      "{"
+      PathExpression
+        "b"
+      "}"
+  MatchArm
+    WildcardPattern
+      "_"
+    "->"
+    PathExpression
+      "c"
+  "}"
+"#);
+    }
+
+    #[test]
+    fn test_match_binding() {
+        check("match x { y -> y + 1, }", r#"
+MatchExpression
+  "match"
+  PathExpression
+    "x"
+  "{"
+  MatchArm
+    BindingPattern
+      "y"
+    "->"
+    BinaryExpression
+      PathExpression
+        "y"
+      "+"
+      LiteralExpression
+        "1"
+    ","
+  "}"
+"#);
+    }
+
+    #[test]
+    fn test_match_malformed_arm() {
+        let parse = parse("let x: T = match x { (a) -> 1, 2 -> 3 4 -> 5 };");
+        assert_eq!(parse.errors(), [
+            ParseError { kind: ParseErrorKind::ExpectedPattern, span: Span::new(21, 24) },
+            ParseError { kind: ParseErrorKind::Expected(TokenKind::Comma), span: Span::empty(37) },
+        ]);
+        let arms = parse.syntax().descendants().filter(|node| node.kind() == SyntaxKind::MatchArm).count();
+        assert_eq!(arms, 3);
+    }
+
+    #[test]
+    fn test_shift_after_generic_type() {
+        let parse = parse("let x: List<List<Integer>> = a >> b;");
+        assert_eq!(parse.errors(), []);
+        let mut output = String::new();
+        render(&parse.syntax(), 0, &mut output);
+        assert_eq!(format!("\n{output}"), r#"
+File
+  Field
+    "let"
+    "x"
+    ":"
+    Type
+      PathExpression
+        "List"
+      GenericArguments
+        "<"
+        Type
+          PathExpression
+            "List"
+          GenericArguments
+            "<"
+            Type
+              PathExpression
+                "Integer"
+            ">"
+        ">"
+    "="
+    BinaryExpression
+      PathExpression
+        "a"
+      ">>"
+      PathExpression
+        "b"
+    ";"
+  ""
+"#);
+    }
+
+    #[test]
+    fn test_generic_type_before_initializer() {
+        let parse = parse("let x: List<Integer>= a;");
+        assert_eq!(parse.errors(), []);
+        let types = parse.syntax().descendants().filter(|node| node.kind() == SyntaxKind::Type).count();
+        assert_eq!(types, 2);
+    }
+
+    #[test]
+    fn test_path() {
+        check("math::Integer::parse(a)", r#"
+CallExpression
+  PathExpression
+    "math"
+    "::"
+    "Integer"
+    "::"
+    "parse"
+  Arguments
+    "("
+    PathExpression
+      "a"
+    ")"
+"#);
+    }
+
+    #[test]
+    fn test_path_missing_segment() {
+        let parse = parse("let x: T = a::;");
+        let kinds = parse.errors().iter().map(|error| error.kind).collect::<Vec<_>>();
+        assert_eq!(kinds, [ParseErrorKind::Expected(TokenKind::Identifier)]);
+    }
+
+    #[test]
+    fn test_call() {
+        check("-foo(a, bar(), b + 1,)(c)", r#"
+PrefixExpression
+  "-"
+  CallExpression
+    CallExpression
+      PathExpression
+        "foo"
+      Arguments
+        "("
+        PathExpression
+          "a"
+        ","
+        CallExpression
+          PathExpression
+            "bar"
+          Arguments
+            "("
+            ")"
+        ","
+        BinaryExpression
+          PathExpression
+            "b"
+          "+"
+          LiteralExpression
+            "1"
+        ","
+        ")"
+    Arguments
+      "("
+      PathExpression
+        "c"
+      ")"
+"#);
+    }
+
+    #[test]
+    fn test_missing_comma_in_arguments() {
+        let parse = parse("let x: T = foo(a b);");
+        assert_eq!(parse.errors(), [
+            ParseError { kind: ParseErrorKind::Expected(TokenKind::Comma), span: Span::new(17, 18) },
+        ]);
+        let calls = parse.syntax().descendants()
+            .filter(|node| node.kind() == SyntaxKind::CallExpression)
+            .collect::<Vec<_>>();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].text(), "foo(a b)");
+        assert_eq!(parse.syntax().children().count(), 1);
+    }
+
+    #[test]
+    fn test_malformed_argument() {
+        let parse = parse("let x: T = foo(a b, c);");
+        assert_eq!(parse.errors(), [
+            ParseError { kind: ParseErrorKind::Expected(TokenKind::Comma), span: Span::new(17, 18) },
+        ]);
+        let arguments = parse.syntax().descendants()
+            .find(|node| node.kind() == SyntaxKind::Arguments)
+            .unwrap();
+        let mut output = String::new();
+        render(&arguments, 0, &mut output);
+        assert_eq!(format!("\n{output}"), r#"
+Arguments
+  "("
+  PathExpression
+    "a"
+  Error
+    "b"
+  ","
+  PathExpression
+    "c"
+  ")"
+"#);
+    }
+
+    #[test]
+    fn test_block_statements() {
+        check("{ let mutable y: T = 1; { y; } y = 2; y }", r#"
+BlockExpression
+  "{"
+  Statement
+    Field
+      "let"
+      "mutable"
+      "y"
+      ":"
+      Type
+        PathExpression
+          "T"
+      "="
+      LiteralExpression
+        "1"
+      ";"
+  Statement
+    BlockExpression
+      "{"
+      Statement
+        PathExpression
+          "y"
+        ";"
+      "}"
+  Statement
+    BinaryExpression
+      PathExpression
+        "y"
+      "="
+      LiteralExpression
+        "2"
+    ";"
+  PathExpression
+    "y"
+  "}"
+"#);
+    }
+
+    #[test]
+    fn test_nested_block_value() {
+        check("{ { 1 } }", r#"
+BlockExpression
+  "{"
+  BlockExpression
+    "{"
+    LiteralExpression
+      "1"
+    "}"
+  "}"
+"#);
+    }
+
+    #[test]
+    fn test_missing_operand() {
+        let parse = parse("let x: T = 1 + ;");
+        assert_eq!(parse.errors().len(), 1);
+        assert_eq!(parse.errors()[0].kind, ParseErrorKind::ExpectedExpression);
+    }
+}
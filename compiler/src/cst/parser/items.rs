@@ -0,0 +1,323 @@
+//! Grammar of items, the declarations a file and a class are made of.
+
+use super::{Checkpoint, ParseErrorKind, Parser};
+use super::expressions::{arguments, block_expression, expression, path_expression, type_};
+use crate::cst::{KeywordKind, TokenKind};
+use crate::cst::tree::SyntaxKind;
+
+pub(super) fn file(p: &mut Parser) {
+    p.start(SyntaxKind::File);
+    items(p);
+    p.bump();
+    p.finish();
+}
+
+/// Parse items until the closing delimiter of an open construct, without consuming it.
+fn items(p: &mut Parser) {
+    while !p.at_close() && !p.at_end() {
+        item(p);
+    }
+}
+
+/// Parse an item with the attributes, the visibility and the modifiers before it.
+///
+/// The attributes, the visibility and the modifiers are children of the item they are attached
+/// to. If they are not followed by an item, they are left in the enclosing tree. Any item accepts
+/// any modifiers, and [`crate::cst::validation`] reports the ones which are not allowed.
+pub(super) fn item(p: &mut Parser) {
+    let checkpoint = p.checkpoint();
+    while p.at(TokenKind::At) {
+        attribute(p);
+    }
+    visibility(p);
+    modifiers(p);
+    match p.peek() {
+        TokenKind::Keyword(KeywordKind::Module) => module(p, checkpoint),
+        TokenKind::Keyword(KeywordKind::Class) => class(p, checkpoint),
+        TokenKind::Keyword(KeywordKind::Enum) => enum_(p, checkpoint),
+        TokenKind::Keyword(KeywordKind::Struct) => struct_(p, checkpoint),
+        TokenKind::Keyword(KeywordKind::Function) => function(p, checkpoint),
+        TokenKind::Keyword(KeywordKind::Field) => field_at(p, checkpoint),
+        _ => p.error_and_bump(ParseErrorKind::ExpectedItem)
+    }
+}
+
+/// `@inline` or `@cfg(test)`
+fn attribute(p: &mut Parser) {
+    p.start(SyntaxKind::Attribute);
+    p.bump();
+    if p.at(TokenKind::Identifier) {
+        path_expression(p);
+        if p.at(TokenKind::LeftParentheses) {
+            arguments(p);
+        }
+    } else {
+        p.error(ParseErrorKind::Expected(TokenKind::Identifier));
+    }
+    p.finish();
+}
+
+/// `module foo;` or `module foo { ... }`
+///
+/// A module without a closing brace contains all items up to the end of the file.
+fn module(p: &mut Parser, checkpoint: Checkpoint) {
+    p.start_at(checkpoint, SyntaxKind::Module);
+    p.bump();
+    if p.at(TokenKind::LeftParentheses) {
+        parameters(p);
+    }
+    p.expect(TokenKind::Identifier);
+    if p.at(TokenKind::LeftBrace) {
+        p.open(TokenKind::LeftBrace);
+        items(p);
+        p.close();
+    } else {
+        p.expect(TokenKind::Semicolon);
+    }
+    p.finish();
+}
+
+/// `class Foo(x: Integer): Bar { ... }`
+fn class(p: &mut Parser, checkpoint: Checkpoint) {
+    p.start_at(checkpoint, SyntaxKind::Class);
+    p.bump();
+    p.expect(TokenKind::Identifier);
+    if p.at(TokenKind::LessThan) {
+        generic_parameters(p);
+    }
+    if p.at(TokenKind::LeftParentheses) {
+        parameters(p);
+    }
+    if p.at(TokenKind::Colon) {
+        inherits(p);
+    }
+    if p.open(TokenKind::LeftBrace) {
+        items(p);
+        p.close();
+    }
+    p.finish();
+}
+
+/// `: Bar, Baz`
+fn inherits(p: &mut Parser) {
+    p.start(SyntaxKind::Inherits);
+    p.bump();
+    type_(p);
+    while p.eat(TokenKind::Comma) {
+        type_(p);
+    }
+    p.finish();
+}
+
+/// `public` or `private`
+///
+/// Like the other modifiers, any number of visibility keywords is accepted, and
+/// [`crate::cst::validation`] reports the ones after the first.
+fn visibility(p: &mut Parser) {
+    while p.eat(TokenKind::Keyword(KeywordKind::Public)) || p.eat(TokenKind::Keyword(KeywordKind::Private)) {}
+}
+
+/// The tokens at which parsing continues after a malformed variant or struct field.
+const MEMBER_RECOVERY: &[TokenKind] = &[
+    TokenKind::Comma,
+    TokenKind::RightBrace,
+];
+
+/// `enum Color { Red, Green, Blue }`
+fn enum_(p: &mut Parser, checkpoint: Checkpoint) {
+    p.start_at(checkpoint, SyntaxKind::Enum);
+    p.bump();
+    p.expect(TokenKind::Identifier);
+    if p.at(TokenKind::LessThan) {
+        generic_parameters(p);
+    }
+    if p.open(TokenKind::LeftBrace) {
+        while !p.at_close() && !p.at_end() {
+            if p.at(TokenKind::Identifier) {
+                variant(p);
+            }
+            if !p.at_any(MEMBER_RECOVERY) {
+                p.error_until(ParseErrorKind::Expected(TokenKind::Identifier), MEMBER_RECOVERY);
+            }
+            if !p.eat(TokenKind::Comma) {
+                break;
+            }
+        }
+        p.close();
+    }
+    p.finish();
+}
+
+/// `Red`
+fn variant(p: &mut Parser) {
+    p.start(SyntaxKind::Variant);
+    p.bump();
+    p.finish();
+}
+
+/// `struct Point { x: Integer, mutable y: Integer }`
+///
+/// A struct only holds data: unlike a class, it has no parameters, parents or methods.
+fn struct_(p: &mut Parser, checkpoint: Checkpoint) {
+    p.start_at(checkpoint, SyntaxKind::Struct);
+    p.bump();
+    p.expect(TokenKind::Identifier);
+    if p.at(TokenKind::LessThan) {
+        generic_parameters(p);
+    }
+    if p.open(TokenKind::LeftBrace) {
+        while !p.at_close() && !p.at_end() {
+            if p.at_any(PARAMETER_START) {
+                struct_field(p);
+            }
+            if !p.at_any(MEMBER_RECOVERY) {
+                p.error_until(ParseErrorKind::Expected(TokenKind::Identifier), MEMBER_RECOVERY);
+            }
+            if !p.eat(TokenKind::Comma) {
+                break;
+            }
+        }
+        p.close();
+    }
+    p.finish();
+}
+
+/// `mutable x: Integer`
+fn struct_field(p: &mut Parser) {
+    p.start(SyntaxKind::StructField);
+    modifiers(p);
+    p.expect(TokenKind::Identifier);
+    if p.expect(TokenKind::Colon) {
+        type_(p);
+    }
+    p.finish();
+}
+
+/// The tokens a parameter, or a field of a struct, can start with.
+const PARAMETER_START: &[TokenKind] = &[
+    TokenKind::Identifier,
+    TokenKind::Keyword(KeywordKind::Constant),
+    TokenKind::Keyword(KeywordKind::Mutable),
+];
+
+/// The tokens at which parsing continues after a malformed parameter.
+const PARAMETER_RECOVERY: &[TokenKind] = &[
+    TokenKind::Comma,
+    TokenKind::RightParentheses,
+    TokenKind::RightArrow,
+    TokenKind::LeftBrace,
+];
+
+/// `constant function map<T, U>(x: T) -> U { ... }`
+///
+/// A function without a return type returns nothing.
+fn function(p: &mut Parser, checkpoint: Checkpoint) {
+    p.start_at(checkpoint, SyntaxKind::Function);
+    p.bump();
+    p.expect(TokenKind::Identifier);
+    if p.at(TokenKind::LessThan) {
+        generic_parameters(p);
+    }
+    if p.at(TokenKind::LeftParentheses) {
+        parameters(p);
+    } else {
+        p.error(ParseErrorKind::Expected(TokenKind::LeftParentheses));
+    }
+    if p.eat(TokenKind::RightArrow) {
+        type_(p);
+    }
+    if p.at(TokenKind::LeftBrace) {
+        block_expression(p);
+    } else {
+        p.error(ParseErrorKind::Expected(TokenKind::LeftBrace));
+    }
+    p.finish();
+}
+
+/// `<T, U>`
+fn generic_parameters(p: &mut Parser) {
+    p.start(SyntaxKind::GenericParameters);
+    p.bump();
+    while !p.at(TokenKind::GreaterThan) && !p.at_end() {
+        if !p.at(TokenKind::Identifier) {
+            p.error(ParseErrorKind::Expected(TokenKind::Identifier));
+            break;
+        }
+        p.start(SyntaxKind::GenericParameter);
+        p.bump();
+        p.finish();
+        if !p.at(TokenKind::GreaterThan) && !p.expect(TokenKind::Comma) {
+            break;
+        }
+    }
+    p.expect(TokenKind::GreaterThan);
+    p.finish();
+}
+
+/// `(x: Integer, mutable y: Integer)`
+///
+/// The tokens of a malformed parameter are skipped up to the next comma, so the parameters after
+/// it are still parsed.
+fn parameters(p: &mut Parser) {
+    p.start(SyntaxKind::Parameters);
+    p.open(TokenKind::LeftParentheses);
+    while !p.at_close() && !p.at_end() {
+        if p.at_any(PARAMETER_START) {
+            parameter(p);
+        }
+        if !p.at_any(PARAMETER_RECOVERY) {
+            p.error_until(ParseErrorKind::ExpectedParameter, PARAMETER_RECOVERY);
+        }
+        if !p.eat(TokenKind::Comma) {
+            break;
+        }
+    }
+    p.close();
+    p.finish();
+}
+
+/// `constant mutable x: Integer`
+///
+/// The type is optional so that the self parameter can leave it out. Whether a parameter may
+/// leave out its type is not checked by the parser.
+fn parameter(p: &mut Parser) {
+    p.start(SyntaxKind::Parameter);
+    modifiers(p);
+    p.expect(TokenKind::Identifier);
+    if p.eat(TokenKind::Colon) {
+        type_(p);
+    }
+    p.finish();
+}
+
+/// `let constant x: Integer = 1;` or `let x: Integer;`
+///
+/// Whether a field may leave out its initializer is not checked by the parser.
+pub(super) fn field(p: &mut Parser) {
+    let checkpoint = p.checkpoint();
+    field_at(p, checkpoint);
+}
+
+/// Parse a field, which also contains everything parsed since the checkpoint.
+fn field_at(p: &mut Parser, checkpoint: Checkpoint) {
+    p.start_at(checkpoint, SyntaxKind::Field);
+    p.bump();
+    modifiers(p);
+    p.expect(TokenKind::Identifier);
+    if p.expect(TokenKind::Colon) {
+        type_(p);
+    }
+    if p.eat(TokenKind::Equals) {
+        expression(p);
+    }
+    p.expect(TokenKind::Semicolon);
+    p.finish();
+}
+
+/// `constant mutable`
+///
+/// The modifiers are accepted in any order and any number of times, so that semantic analysis can
+/// report the combinations which are not allowed.
+fn modifiers(p: &mut Parser) {
+    while p.eat(TokenKind::Keyword(KeywordKind::Constant)) || p.eat(TokenKind::Keyword(KeywordKind::Mutable)) {}
+}
@@ -0,0 +1,1193 @@
+//! Parser responsible for converting a stream of tokens into a concrete syntax tree.
+
+mod event;
+mod expressions;
+mod items;
+mod patterns;
+mod reparse;
+
+use std::fmt;
+use event::Event;
+pub use expressions::{precedence, Associativity};
+use super::{Token, TokenKind};
+use super::lexer::{Lexer, TokenBuffer};
+use super::syntax::SyntaxNode;
+use super::tree::{GreenNode, SyntaxKind};
+use crate::span::Span;
+use crate::text_edit::TextEdit;
+
+/// Parse source code into a concrete syntax tree.
+///
+/// Parsing never fails. Errors are collected while parsing continues, and tokens which could not
+/// be parsed are wrapped in a [`SyntaxKind::Error`] tree. All tokens of the source code, including
+/// trivia, are part of the tree, so [`SyntaxNode::text`] of the root is identical to the source
+/// code.
+pub fn parse(text: &str) -> Parse {
+    let mut parser = Parser::new(Lexer::new(text));
+    items::file(&mut parser);
+    parser.into_parse()
+}
+
+/// Parse source code which has already been lexed into a token buffer.
+///
+/// The result is identical to [`parse`] of the source code of the buffer.
+pub fn parse_tokens(tokens: &TokenBuffer) -> Parse {
+    let mut parser = Parser::new(Lexer::from_tokens(tokens));
+    items::file(&mut parser);
+    parser.into_parse()
+}
+
+/// Parse source code containing a single expression, such as `a + 1`.
+///
+/// Like [`parse`], the root of the tree is a [`SyntaxKind::File`], which contains the expression.
+/// Any tokens after the expression are reported and wrapped in an error tree.
+pub fn parse_expression(text: &str) -> Parse {
+    parse_fragment(text, expressions::expression)
+}
+
+/// Parse source code containing a single type, such as `math::Integer`.
+///
+/// See [`parse_expression`] for the shape of the tree.
+pub fn parse_type(text: &str) -> Parse {
+    parse_fragment(text, expressions::type_)
+}
+
+/// Parse source code containing a single item, such as a class or a function.
+///
+/// See [`parse_expression`] for the shape of the tree.
+pub fn parse_item(text: &str) -> Parse {
+    parse_fragment(text, items::item)
+}
+
+fn parse_fragment(text: &str, fragment: fn(&mut Parser)) -> Parse {
+    let mut parser = Parser::new(Lexer::new(text));
+    parser.start(SyntaxKind::File);
+    fragment(&mut parser);
+    if !parser.at_end() {
+        parser.error_until(ParseErrorKind::Expected(TokenKind::EndOfFile), &[]);
+    }
+    parser.bump();
+    parser.finish();
+    parser.into_parse()
+}
+
+/// The result of parsing some source code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parse {
+    green: GreenNode,
+    errors: Vec<ParseError>,
+}
+
+impl Parse {
+    pub fn green(&self) -> &GreenNode {
+        &self.green
+    }
+
+    /// Returns the root of the syntax tree.
+    pub fn syntax(&self) -> SyntaxNode {
+        SyntaxNode::new_root(self.green.clone())
+    }
+
+    pub fn errors(&self) -> &[ParseError] {
+        &self.errors
+    }
+
+    /// Parse the source code again after an edit.
+    ///
+    /// Only the smallest block containing the edit is parsed again if possible, and the rest of
+    /// the tree is shared with this tree. Otherwise, the edited source code is parsed from scratch.
+    /// Either way, the result is the same as parsing the edited source code.
+    pub fn reparse(&self, edit: &TextEdit) -> Parse {
+        reparse::reparse_block(self, edit)
+            .unwrap_or_else(|| parse(&edit.apply(&self.green.text())))
+    }
+}
+
+/// An error found while parsing, e.g. a missing token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub span: Span,
+}
+
+impl ParseError {
+    /// Returns this error with all of its spans mapped, such as when they are moved by an edit.
+    fn map_spans(self, map: impl Fn(Span) -> Span) -> ParseError {
+        let kind = match self.kind {
+            ParseErrorKind::Unclosed { close, open } => ParseErrorKind::Unclosed { close, open: map(open) },
+            kind => kind
+        };
+        ParseError {
+            kind,
+            span: map(self.span),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A token of a specific kind was expected.
+    Expected(TokenKind),
+    ExpectedItem,
+    ExpectedParameter,
+    ExpectedExpression,
+    ExpectedType,
+    ExpectedPattern,
+    /// The closing delimiter of a construct is missing. The error is reported where the closing
+    /// delimiter was expected, and points at the opening delimiter through `open`.
+    Unclosed {
+        close: TokenKind,
+        open: Span,
+    },
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::Expected(kind) => write!(f, "expected {}", kind),
+            ParseErrorKind::ExpectedItem => write!(f, "expected an item"),
+            ParseErrorKind::ExpectedParameter => write!(f, "expected a parameter"),
+            ParseErrorKind::ExpectedExpression => write!(f, "expected an expression"),
+            ParseErrorKind::ExpectedType => write!(f, "expected a type"),
+            ParseErrorKind::ExpectedPattern => write!(f, "expected a pattern"),
+            ParseErrorKind::Unclosed { close, .. } => write!(f, "unclosed delimiter, expected {}", close),
+        }
+    }
+}
+
+/// Describes the concrete syntax tree of the tokens of a lexer as a list of [`Event`]s.
+///
+/// Trivia is skipped when the next token is inspected, and only added to the tree right before
+/// the next token or tree, so trees start and end with a significant token.
+struct Parser<'text> {
+    lexer: Lexer<'text>,
+    events: Vec<Event<'text>>,
+    /// Trivia which has been skipped, but not added to the tree yet.
+    trivia: Vec<Token<'text>>,
+    errors: Vec<ParseError>,
+    /// The closing delimiters of the constructs which are open, with the span of their opening
+    /// delimiter. The innermost construct is last.
+    delimiters: Vec<(TokenKind, Span)>,
+    /// The end of the last significant token consumed.
+    end: usize,
+}
+
+/// A position in the current tree, at which a tree can be started after its first children have
+/// already been parsed.
+#[derive(Debug, Clone, Copy)]
+struct Checkpoint {
+    event: usize,
+}
+
+impl<'text> Parser<'text> {
+    fn new(lexer: Lexer<'text>) -> Self {
+        Self {
+            lexer,
+            events: Vec::new(),
+            trivia: Vec::new(),
+            errors: Vec::new(),
+            delimiters: Vec::new(),
+            end: 0,
+        }
+    }
+
+    fn into_parse(self) -> Parse {
+        Parse {
+            green: event::build(self.events),
+            errors: self.errors,
+        }
+    }
+
+    /// Start a new tree inside the current tree.
+    fn start(&mut self, kind: SyntaxKind) {
+        if !self.events.is_empty() {
+            self.skip_trivia();
+            self.attach_trivia();
+        }
+        self.events.push(Event::Start {
+            kind,
+            forward_parent: None,
+        });
+    }
+
+    /// Finish the current tree and add it to its parent.
+    fn finish(&mut self) {
+        self.events.push(Event::Finish);
+    }
+
+    fn checkpoint(&mut self) -> Checkpoint {
+        self.skip_trivia();
+        self.attach_trivia();
+        Checkpoint {
+            event: self.events.len(),
+        }
+    }
+
+    /// Start a new tree containing everything parsed in the current tree since the checkpoint.
+    fn start_at(&mut self, checkpoint: Checkpoint, kind: SyntaxKind) {
+        let start = Event::Start {
+            kind,
+            forward_parent: None,
+        };
+        let mut event = checkpoint.event;
+        if !matches!(self.events.get(event), Some(Event::Start { .. })) {
+            // The tree does not start with a child tree which can point forward to it.
+            self.events.insert(event, start);
+            return;
+        }
+        // Point the outermost tree already started at the checkpoint forward to the new tree.
+        let parent = self.events.len();
+        while let Event::Start { forward_parent, .. } = &mut self.events[event] {
+            match forward_parent {
+                Some(next) => event = *next,
+                None => {
+                    *forward_parent = Some(parent);
+                    break;
+                }
+            }
+        }
+        self.events.push(start);
+    }
+
+    /// Returns the kind of the next significant token.
+    fn peek(&mut self) -> TokenKind {
+        self.skip_trivia();
+        self.lexer.peek().map_or(TokenKind::EndOfFile, Token::kind)
+    }
+
+    /// Returns the text of the next significant token.
+    fn peek_text(&mut self) -> &'text str {
+        self.skip_trivia();
+        self.lexer.peek().map_or("", Token::text)
+    }
+
+    /// Check if the upcoming tokens can be interpreted as the given kind.
+    fn at(&mut self, kind: TokenKind) -> bool {
+        self.skip_trivia();
+        self.lexer.peek_kind(kind).is_some()
+    }
+
+    fn at_any(&mut self, kinds: &[TokenKind]) -> bool {
+        kinds.iter().any(|&kind| self.at(kind))
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.peek() == TokenKind::EndOfFile
+    }
+
+    /// Add the next significant token to the current tree, whatever its kind.
+    fn bump(&mut self) {
+        self.skip_trivia();
+        if let Some(token) = self.lexer.next() {
+            self.push(token);
+        }
+    }
+
+    /// Add the upcoming tokens to the current tree if they can be interpreted as the given kind.
+    fn eat(&mut self, kind: TokenKind) -> bool {
+        self.skip_trivia();
+        match self.lexer.next_kind(kind) {
+            Some(token) => {
+                self.push(token);
+                true
+            }
+            None => false
+        }
+    }
+
+    /// Like [`Parser::eat`], but reports an error if the tokens are not of the given kind.
+    fn expect(&mut self, kind: TokenKind) -> bool {
+        let eaten = self.eat(kind);
+        if !eaten {
+            self.error(ParseErrorKind::Expected(kind));
+        }
+        eaten
+    }
+
+    /// Like [`Parser::expect`] for an opening delimiter, which is tracked until the construct is
+    /// closed by [`Parser::close`].
+    fn open(&mut self, kind: TokenKind) -> bool {
+        let Some(close) = kind.matching_close() else {
+            unreachable!("{kind} is not an opening delimiter");
+        };
+        self.skip_trivia();
+        let span = self.lexer.peek().map_or(Span::empty(self.end), Token::span);
+        let opened = self.expect(kind);
+        if opened {
+            self.delimiters.push((close, span));
+        }
+        opened
+    }
+
+    /// Add the closing delimiter of the innermost open construct to the current tree, or report
+    /// the construct as unclosed if the delimiter is missing.
+    fn close(&mut self) {
+        let Some((close, open)) = self.delimiters.pop() else {
+            return;
+        };
+        if !self.eat(close) {
+            self.error(ParseErrorKind::Unclosed { close, open });
+        }
+    }
+
+    /// Check if the next token closes one of the open constructs.
+    ///
+    /// Lists and bodies end at such a token, even if it closes an outer construct, so that a
+    /// missing delimiter only affects the innermost construct.
+    fn at_close(&mut self) -> bool {
+        let next = self.peek();
+        self.delimiters.iter().any(|&(close, _)| close == next)
+    }
+
+    /// Report an error right after the last significant token.
+    fn error(&mut self, kind: ParseErrorKind) {
+        self.errors.push(ParseError {
+            kind,
+            span: Span::empty(self.end),
+        });
+    }
+
+    /// Report an error at the next significant token and wrap it in an error tree.
+    fn error_and_bump(&mut self, kind: ParseErrorKind) {
+        self.skip_trivia();
+        let span = self.lexer.peek().map_or(Span::empty(self.end), Token::span);
+        self.errors.push(ParseError {
+            kind,
+            span,
+        });
+        self.start(SyntaxKind::Error);
+        self.bump();
+        self.finish();
+    }
+
+    /// Report an error at the next significant tokens and wrap them in an error tree, up to a token
+    /// of one of the given kinds, a token closing an open construct, or the end of the file.
+    ///
+    /// Delimited groups in the skipped tokens are skipped as a whole, so a token of one of the
+    /// given kinds inside parentheses does not end the error.
+    fn error_until(&mut self, kind: ParseErrorKind, until: &[TokenKind]) {
+        self.skip_trivia();
+        let start = self.lexer.peek().map_or(self.end, |token| token.span().start());
+        self.start(SyntaxKind::Error);
+        let mut nested = Vec::new();
+        while !self.at_end() {
+            let next = self.peek();
+            if nested.last() == Some(&next) {
+                nested.pop();
+            } else if self.at_close() || nested.is_empty() && self.at_any(until) {
+                break;
+            } else if let Some(close) = next.matching_close() {
+                nested.push(close);
+            }
+            self.bump();
+        }
+        self.finish();
+        self.errors.push(ParseError {
+            kind,
+            span: Span::new(start, self.end.max(start)),
+        });
+    }
+
+    /// Skip upcoming trivia, until it is added to the tree by [`Parser::attach_trivia`].
+    fn skip_trivia(&mut self) {
+        while let Some(token) = self.lexer.peek().filter(|token| token.kind().is_trivia()) {
+            self.lexer.next();
+            self.trivia.push(token);
+        }
+    }
+
+    /// Add skipped trivia to the current tree.
+    fn attach_trivia(&mut self) {
+        for token in self.trivia.drain(..) {
+            self.events.push(Event::Token {
+                kind: token.kind(),
+                text: token.text(),
+            });
+        }
+    }
+
+    fn push(&mut self, token: Token<'text>) {
+        self.attach_trivia();
+        if token.kind() != TokenKind::EndOfFile {
+            self.end = token.span().end();
+        }
+        self.events.push(Event::Token {
+            kind: token.kind(),
+            text: token.text(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::syntax::SyntaxElement;
+
+    /// Renders a tree with one node per line, leaving out trivia.
+    pub(super) fn render(node: &SyntaxNode, depth: usize, output: &mut String) {
+        output.push_str(&format!("{}{:?}\n", "  ".repeat(depth), node.kind()));
+        for child in node.children_with_tokens() {
+            match child {
+                SyntaxElement::Node(node) => render(&node, depth + 1, output),
+                SyntaxElement::Token(token) if !token.kind().is_trivia() => {
+                    output.push_str(&format!("{}{:?}\n", "  ".repeat(depth + 1), token.text()));
+                }
+                SyntaxElement::Token(_) => {}
+            }
+        }
+    }
+
+    /// Parses the text and compares the rendered tree and the errors.
+    fn check(text: &str, expected: &str, errors: &[ParseError]) {
+        check_parse(parse(text), expected, errors);
+    }
+
+    fn check_parse(parse: Parse, expected: &str, errors: &[ParseError]) {
+        let mut output = String::new();
+        render(&parse.syntax(), 0, &mut output);
+        assert_eq!(output, expected.trim_start());
+        assert_eq!(parse.errors(), errors);
+    }
+
+    #[test]
+    fn test_empty_file() {
+        check("", r#"
+File
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_module() {
+        check("#!/usr/bin/env zinc\nmodule foo;\n", r#"
+File
+  Module
+    "module"
+    "foo"
+    ";"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_module_with_body() {
+        check("module foo {\n    module bar;\n    let x: Integer = 1;\n}", r#"
+File
+  Module
+    "module"
+    "foo"
+    "{"
+    Module
+      "module"
+      "bar"
+      ";"
+    Field
+      "let"
+      "x"
+      ":"
+      Type
+        PathExpression
+          "Integer"
+      "="
+      LiteralExpression
+        "1"
+      ";"
+    "}"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_unclosed_module() {
+        check("module foo {\n    class Bar {}\n", r#"
+File
+  Module
+    "module"
+    "foo"
+    "{"
+    Class
+      "class"
+      "Bar"
+      "{"
+      "}"
+  ""
+"#, &[ParseError { kind: ParseErrorKind::Unclosed { close: TokenKind::RightBrace, open: Span::new(11, 12) }, span: Span::empty(29) }]);
+    }
+
+    #[test]
+    fn test_class() {
+        check("class Foo: Bar, Baz {\n    let x: Integer = 1;\n}", r#"
+File
+  Class
+    "class"
+    "Foo"
+    Inherits
+      ":"
+      Type
+        PathExpression
+          "Bar"
+      ","
+      Type
+        PathExpression
+          "Baz"
+    "{"
+    Field
+      "let"
+      "x"
+      ":"
+      Type
+        PathExpression
+          "Integer"
+      "="
+      LiteralExpression
+        "1"
+      ";"
+    "}"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_enum() {
+        check("enum Color { Red, Green, Blue }", r#"
+File
+  Enum
+    "enum"
+    "Color"
+    "{"
+    Variant
+      "Red"
+    ","
+    Variant
+      "Green"
+    ","
+    Variant
+      "Blue"
+    "}"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_malformed_variant() {
+        check("enum Color { Red, 1 Green, Blue }", r#"
+File
+  Enum
+    "enum"
+    "Color"
+    "{"
+    Variant
+      "Red"
+    ","
+    Error
+      "1"
+      "Green"
+    ","
+    Variant
+      "Blue"
+    "}"
+  ""
+"#, &[ParseError { kind: ParseErrorKind::Expected(TokenKind::Identifier), span: Span::new(18, 25) }]);
+    }
+
+    #[test]
+    fn test_struct() {
+        check("struct Point { x: Integer, mutable y: Integer }", r#"
+File
+  Struct
+    "struct"
+    "Point"
+    "{"
+    StructField
+      "x"
+      ":"
+      Type
+        PathExpression
+          "Integer"
+    ","
+    StructField
+      "mutable"
+      "y"
+      ":"
+      Type
+        PathExpression
+          "Integer"
+    "}"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_struct_field_without_type() {
+        check("struct Point { x, y: Integer }", r#"
+File
+  Struct
+    "struct"
+    "Point"
+    "{"
+    StructField
+      "x"
+    ","
+    StructField
+      "y"
+      ":"
+      Type
+        PathExpression
+          "Integer"
+    "}"
+  ""
+"#, &[ParseError { kind: ParseErrorKind::Expected(TokenKind::Colon), span: Span::empty(16) }]);
+    }
+
+    #[test]
+    fn test_attributes() {
+        check("@test @cfg(debug) function foo() {}", r#"
+File
+  Function
+    Attribute
+      "@"
+      PathExpression
+        "test"
+    Attribute
+      "@"
+      PathExpression
+        "cfg"
+      Arguments
+        "("
+        PathExpression
+          "debug"
+        ")"
+    "function"
+    "foo"
+    Parameters
+      "("
+      ")"
+    BlockExpression
+      "{"
+      "}"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_attribute_on_member() {
+        check("class Foo { @inline let x: T; }", r#"
+File
+  Class
+    "class"
+    "Foo"
+    "{"
+    Field
+      Attribute
+        "@"
+        PathExpression
+          "inline"
+      "let"
+      "x"
+      ":"
+      Type
+        PathExpression
+          "T"
+      ";"
+    "}"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_visibility() {
+        check("@inline public constant function foo() {} private class Foo { public let x: T; }", r#"
+File
+  Function
+    Attribute
+      "@"
+      PathExpression
+        "inline"
+    "public"
+    "constant"
+    "function"
+    "foo"
+    Parameters
+      "("
+      ")"
+    BlockExpression
+      "{"
+      "}"
+  Class
+    "private"
+    "class"
+    "Foo"
+    "{"
+    Field
+      "public"
+      "let"
+      "x"
+      ":"
+      Type
+        PathExpression
+          "T"
+      ";"
+    "}"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_attribute_without_item() {
+        check("@ ;", r#"
+File
+  Attribute
+    "@"
+  Error
+    ";"
+  ""
+"#, &[
+            ParseError { kind: ParseErrorKind::Expected(TokenKind::Identifier), span: Span::empty(1) },
+            ParseError { kind: ParseErrorKind::ExpectedItem, span: Span::new(2, 3) },
+        ]);
+    }
+
+    #[test]
+    fn test_function() {
+        check("constant function foo(mutable self, constant x: Integer) -> Boolean { bar; true }", r#"
+File
+  Function
+    "constant"
+    "function"
+    "foo"
+    Parameters
+      "("
+      Parameter
+        "mutable"
+        "self"
+      ","
+      Parameter
+        "constant"
+        "x"
+        ":"
+        Type
+          PathExpression
+            "Integer"
+      ")"
+    "->"
+    Type
+      PathExpression
+        "Boolean"
+    BlockExpression
+      "{"
+      Statement
+        PathExpression
+          "bar"
+        ";"
+      LiteralExpression
+        "true"
+      "}"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_generic_function() {
+        check("function map<T, U>(x: List<T>) -> List<U> {}", r#"
+File
+  Function
+    "function"
+    "map"
+    GenericParameters
+      "<"
+      GenericParameter
+        "T"
+      ","
+      GenericParameter
+        "U"
+      ">"
+    Parameters
+      "("
+      Parameter
+        "x"
+        ":"
+        Type
+          PathExpression
+            "List"
+          GenericArguments
+            "<"
+            Type
+              PathExpression
+                "T"
+            ">"
+      ")"
+    "->"
+    Type
+      PathExpression
+        "List"
+      GenericArguments
+        "<"
+        Type
+          PathExpression
+            "U"
+        ">"
+    BlockExpression
+      "{"
+      "}"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_unclosed_generic_parameters() {
+        check("class Foo<T { }", r#"
+File
+  Class
+    "class"
+    "Foo"
+    GenericParameters
+      "<"
+      GenericParameter
+        "T"
+    "{"
+    "}"
+  ""
+"#, &[
+            ParseError { kind: ParseErrorKind::Expected(TokenKind::Comma), span: Span::empty(11) },
+            ParseError { kind: ParseErrorKind::Expected(TokenKind::GreaterThan), span: Span::empty(11) },
+        ]);
+    }
+
+    #[test]
+    fn test_function_without_return_type() {
+        check("function foo() {}", r#"
+File
+  Function
+    "function"
+    "foo"
+    Parameters
+      "("
+      ")"
+    BlockExpression
+      "{"
+      "}"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_trailing_commas() {
+        let parse = parse("enum A { B, } struct C<D,> { e: D, } function f(g: List<G,>,) { f(g,); }");
+        assert_eq!(parse.errors(), []);
+        let lists = parse.syntax().descendants()
+            .filter(|node| matches!(node.kind(), SyntaxKind::Enum | SyntaxKind::Struct | SyntaxKind::GenericParameters |
+                SyntaxKind::Parameters | SyntaxKind::GenericArguments | SyntaxKind::Arguments))
+            .filter_map(|node| {
+                let tokens = node.children_with_tokens()
+                    .filter_map(|child| child.as_token().cloned())
+                    .filter(|token| !token.kind().is_trivia())
+                    .map(|token| token.text().to_string())
+                    .collect::<Vec<_>>();
+                let [.., comma, _] = tokens.as_slice() else {
+                    return None;
+                };
+                Some((node.kind(), comma.clone()))
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(lists, [
+            (SyntaxKind::Enum, ",".to_string()),
+            (SyntaxKind::Struct, ",".to_string()),
+            (SyntaxKind::GenericParameters, ",".to_string()),
+            (SyntaxKind::Parameters, ",".to_string()),
+            (SyntaxKind::GenericArguments, ",".to_string()),
+            (SyntaxKind::Arguments, ",".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn test_malformed_parameter() {
+        check("function foo(x: Integer, 1 + 2, y) {}", r#"
+File
+  Function
+    "function"
+    "foo"
+    Parameters
+      "("
+      Parameter
+        "x"
+        ":"
+        Type
+          PathExpression
+            "Integer"
+      ","
+      Error
+        "1"
+        "+"
+        "2"
+      ","
+      Parameter
+        "y"
+      ")"
+    BlockExpression
+      "{"
+      "}"
+  ""
+"#, &[ParseError { kind: ParseErrorKind::ExpectedParameter, span: Span::new(25, 30) }]);
+    }
+
+    #[test]
+    fn test_unclosed_parameters() {
+        check("function foo(x: Integer -> Integer {}", r#"
+File
+  Function
+    "function"
+    "foo"
+    Parameters
+      "("
+      Parameter
+        "x"
+        ":"
+        Type
+          PathExpression
+            "Integer"
+    "->"
+    Type
+      PathExpression
+        "Integer"
+    BlockExpression
+      "{"
+      "}"
+  ""
+"#, &[ParseError { kind: ParseErrorKind::Unclosed { close: TokenKind::RightParentheses, open: Span::new(12, 13) }, span: Span::empty(23) }]);
+    }
+
+    #[test]
+    fn test_unclosed_block_before_item() {
+        check("class Foo { function f() { x; function g() {} }", r#"
+File
+  Class
+    "class"
+    "Foo"
+    "{"
+    Function
+      "function"
+      "f"
+      Parameters
+        "("
+        ")"
+      BlockExpression
+        "{"
+        Statement
+          PathExpression
+            "x"
+          ";"
+    Function
+      "function"
+      "g"
+      Parameters
+        "("
+        ")"
+      BlockExpression
+        "{"
+        "}"
+    "}"
+  ""
+"#, &[ParseError {
+            kind: ParseErrorKind::Unclosed { close: TokenKind::RightBrace, open: Span::new(25, 26) },
+            span: Span::empty(29),
+        }]);
+    }
+
+    #[test]
+    fn test_closed_by_outer_delimiter() {
+        check("let x: T = f({ a );", r#"
+File
+  Field
+    "let"
+    "x"
+    ":"
+    Type
+      PathExpression
+        "T"
+    "="
+    CallExpression
+      PathExpression
+        "f"
+      Arguments
+        "("
+        BlockExpression
+          "{"
+          PathExpression
+            "a"
+        ")"
+    ";"
+  ""
+"#, &[ParseError {
+            kind: ParseErrorKind::Unclosed { close: TokenKind::RightBrace, open: Span::new(13, 14) },
+            span: Span::empty(16),
+        }]);
+    }
+
+    #[test]
+    fn test_error_skips_nested_delimiters() {
+        let parse = parse("function f(x: T, (a, b) y) {}");
+        assert_eq!(parse.errors(), [ParseError { kind: ParseErrorKind::ExpectedParameter, span: Span::new(17, 25) }]);
+        let parameters = parse.syntax().descendants().filter(|node| node.kind() == SyntaxKind::Parameter).count();
+        assert_eq!(parameters, 1);
+    }
+
+    #[test]
+    fn test_missing_semicolon() {
+        check("module foo\nclass Bar {}", r#"
+File
+  Module
+    "module"
+    "foo"
+  Class
+    "class"
+    "Bar"
+    "{"
+    "}"
+  ""
+"#, &[ParseError { kind: ParseErrorKind::Expected(TokenKind::Semicolon), span: Span::empty(10) }]);
+    }
+
+    #[test]
+    fn test_unexpected_token() {
+        check("; class Foo {}", r#"
+File
+  Error
+    ";"
+  Class
+    "class"
+    "Foo"
+    "{"
+    "}"
+  ""
+"#, &[ParseError { kind: ParseErrorKind::ExpectedItem, span: Span::new(0, 1) }]);
+    }
+
+    #[test]
+    fn test_unclosed_class() {
+        check("class Foo { let x: Integer = 1;", r#"
+File
+  Class
+    "class"
+    "Foo"
+    "{"
+    Field
+      "let"
+      "x"
+      ":"
+      Type
+        PathExpression
+          "Integer"
+      "="
+      LiteralExpression
+        "1"
+      ";"
+  ""
+"#, &[ParseError { kind: ParseErrorKind::Unclosed { close: TokenKind::RightBrace, open: Span::new(10, 11) }, span: Span::empty(31) }]);
+    }
+
+    #[test]
+    fn test_dump() {
+        let parse = parse("let x: T = -1;  \n");
+        assert_eq!(parse.syntax().dump(), r#"
+File@0..17
+  Field@0..14
+    Keyword(Field)@0..3 "let"
+    Whitespace@3..4 " "
+    Identifier@4..5 "x"
+    Colon@5..6 ":"
+    Whitespace@6..7 " "
+    Type@7..8
+      PathExpression@7..8
+        Identifier@7..8 "T"
+    Whitespace@8..9 " "
+    Equals@9..10 "="
+    Whitespace@10..11 " "
+    PrefixExpression@11..13
+      Minus@11..12 "-"
+      LiteralExpression@12..13
+        Integer@12..13 "1"
+    Semicolon@13..14 ";"
+  Whitespace@14..17 "  \n"
+  EndOfFile@17..17 ""
+"#.trim_start());
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        let parse = parse_expression(" a + f(1) ");
+        assert_eq!(parse.syntax().text(), " a + f(1) ");
+        check_parse(parse, r#"
+File
+  BinaryExpression
+    PathExpression
+      "a"
+    "+"
+    CallExpression
+      PathExpression
+        "f"
+      Arguments
+        "("
+        LiteralExpression
+          "1"
+        ")"
+  ""
+"#, &[]);
+    }
+
+    #[test]
+    fn test_parse_type() {
+        let parse = parse_type("math::Integer");
+        let ty = parse.syntax().first_child().unwrap();
+        assert_eq!(ty.kind(), SyntaxKind::Type);
+        assert_eq!(ty.text(), "math::Integer");
+        assert_eq!(parse.errors(), []);
+    }
+
+    #[test]
+    fn test_parse_item() {
+        let parse = parse_item("function foo() {}");
+        assert_eq!(parse.syntax().first_child().unwrap().kind(), SyntaxKind::Function);
+        assert_eq!(parse.errors(), []);
+    }
+
+    #[test]
+    fn test_fragment_with_trailing_tokens() {
+        check_parse(parse_expression("1 2 3"), r#"
+File
+  LiteralExpression
+    "1"
+  Error
+    "2"
+    "3"
+  ""
+"#, &[ParseError { kind: ParseErrorKind::Expected(TokenKind::EndOfFile), span: Span::new(2, 5) }]);
+        check_parse(parse_type(""), r#"
+File
+  ""
+"#, &[ParseError { kind: ParseErrorKind::ExpectedType, span: Span::empty(0) }]);
+    }
+
+    #[test]
+    fn test_trivia_is_kept() {
+        let text = "#!zinc\n  module foo ;  \n";
+        assert_eq!(parse(text).syntax().text(), text);
+    }
+
+    #[test]
+    fn test_parse_tokens() {
+        let text = "function foo() -> Integer { a >>= 1 §; if {";
+        assert_eq!(parse_tokens(&Lexer::tokenize(text)), parse(text));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_lossless(text in proptest::prelude::any::<String>()) {
+            proptest::prop_assert_eq!(parse(&text).syntax().text(), text);
+        }
+
+        #[test]
+        fn test_lossless_source_like(text in "(let|function|class|module|constant|mutable|[a-z1]|[ \n]|//|[-+*<>=!&|:;,{}()]|\u{1F468})*") {
+            proptest::prop_assert_eq!(parse(&text).syntax().text(), text);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Grammar of patterns, which match a value and bind its parts to names.
+
+use super::{ParseErrorKind, Parser};
+use crate::cst::TokenKind;
+use crate::cst::tree::SyntaxKind;
+
+/// Parse a pattern, or report an error without consuming anything if there is none.
+pub(super) fn pattern(p: &mut Parser) {
+    match p.peek() {
+        TokenKind::Identifier if p.peek_text() == "_" => wildcard_pattern(p),
+        TokenKind::Identifier => binding_pattern(p),
+        kind if kind.is_literal() => literal_pattern(p),
+        _ => p.error(ParseErrorKind::ExpectedPattern),
+    }
+}
+
+pub(super) fn at_pattern(p: &mut Parser) -> bool {
+    let kind = p.peek();
+    kind == TokenKind::Identifier || kind.is_literal()
+}
+
+/// `item`
+fn binding_pattern(p: &mut Parser) {
+    p.start(SyntaxKind::BindingPattern);
+    p.bump();
+    p.finish();
+}
+
+/// `_`
+fn wildcard_pattern(p: &mut Parser) {
+    p.start(SyntaxKind::WildcardPattern);
+    p.bump();
+    p.finish();
+}
+
+/// `1`, `true`, `none`
+fn literal_pattern(p: &mut Parser) {
+    p.start(SyntaxKind::LiteralPattern);
+    p.bump();
+    p.finish();
+}
@@ -0,0 +1,220 @@
+//! Reparsing of the source code after an edit, reusing the parts of the tree the edit does not
+//! touch.
+
+use super::{expressions, Parse, Parser};
+use crate::cst::TokenKind;
+use crate::cst::lexer::Lexer;
+use crate::cst::syntax::SyntaxNode;
+use crate::cst::tree::{GreenElement, GreenNode, SyntaxKind};
+use crate::span::Span;
+use crate::text_edit::TextEdit;
+
+/// Reparse only the smallest block containing the edit, and splice the new block into the old
+/// tree.
+///
+/// Returns `None` if there is no such block, or if the edited block is no longer a single block
+/// on its own, such as when the edit removes one of its braces.
+pub(super) fn reparse_block(parse: &Parse, edit: &TextEdit) -> Option<Parse> {
+    let block = covering_block(parse.syntax(), edit.span())?;
+    if is_inside_parentheses(&block) {
+        return None;
+    }
+    let span = block.span();
+    let relative = TextEdit::replace(
+        Span::new(edit.span().start() - span.start(), edit.span().end() - span.start()),
+        edit.text(),
+    );
+    let text = relative.apply(&block.green().text());
+    if !is_balanced_block(&text) {
+        return None;
+    }
+    let mut parser = Parser::new(Lexer::new(&text));
+    expressions::block_expression(&mut parser);
+    if !parser.at_end() {
+        return None;
+    }
+    let fragment = parser.into_parse();
+    let green = splice(&block, GreenElement::Node(fragment.green));
+    let delta = text.len() as isize - span.length() as isize;
+    let shift = |offset: usize| offset.checked_add_signed(delta).expect("edit outside the tree");
+    let before = parse.errors.iter()
+        .filter(|error| error.span.end() <= span.start())
+        .copied();
+    let inside = fragment.errors.into_iter()
+        .map(|error| error.map_spans(|inner| Span::new(inner.start() + span.start(), inner.end() + span.start())));
+    let after = parse.errors.iter()
+        .filter(|error| error.span.start() >= span.end())
+        .map(|error| error.map_spans(|outer| if outer.start() >= span.end() {
+            Span::new(shift(outer.start()), shift(outer.end()))
+        } else {
+            outer
+        }));
+    Some(Parse {
+        green,
+        errors: before.chain(inside).chain(after).collect(),
+    })
+}
+
+/// Returns the innermost block which contains the span without touching its braces.
+fn covering_block(root: SyntaxNode, span: Span) -> Option<SyntaxNode> {
+    root.covering_node(span)?
+        .ancestors()
+        .filter(|node| node.kind() == SyntaxKind::BlockExpression)
+        .find(|node| node.span().start() < span.start() && span.end() < node.span().end())
+}
+
+/// Check if the block is inside parentheses.
+///
+/// The parser ends a block at a closing parenthesis of an outer construct, which a block parsed on
+/// its own would not do.
+fn is_inside_parentheses(block: &SyntaxNode) -> bool {
+    block.ancestors().any(|node| matches!(node.kind(),
+        SyntaxKind::ParenthesizedExpression | SyntaxKind::Arguments | SyntaxKind::Parameters))
+}
+
+/// Check if the text is a single block: it starts with an opening brace, and the brace closing
+/// it is the last token.
+fn is_balanced_block(text: &str) -> bool {
+    let tokens = Lexer::tokenize(text);
+    let kinds = tokens.kinds();
+    if kinds.first() != Some(&TokenKind::LeftBrace) {
+        return false;
+    }
+    let mut depth = 0;
+    for (index, kind) in kinds.iter().enumerate() {
+        match kind {
+            TokenKind::LeftBrace => depth += 1,
+            TokenKind::RightBrace => {
+                depth -= 1;
+                if depth == 0 {
+                    return kinds[index + 1..] == [TokenKind::EndOfFile];
+                }
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// Replace the node with a new node, and return the new root of the tree.
+fn splice(node: &SyntaxNode, replacement: GreenElement) -> GreenNode {
+    let mut node = node.clone();
+    let mut replacement = replacement;
+    while let Some(parent) = node.parent() {
+        replacement = GreenElement::Node(parent.green().replace_child(node.index(), replacement));
+        node = parent;
+    }
+    match replacement {
+        GreenElement::Node(root) => root,
+        GreenElement::Token(_) => unreachable!("the root is a node"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::parser::{parse, ParseError};
+
+    /// Applies the edit, and checks that reparsing yields the same tree and errors as parsing the
+    /// edited text from scratch.
+    fn check(text: &str, edit: TextEdit, incremental: bool) {
+        let old = parse(text);
+        let new = old.reparse(&edit);
+        let expected = parse(&edit.apply(text));
+        assert_eq!(new.green(), expected.green());
+        assert_eq!(sorted_errors(&new), sorted_errors(&expected));
+        assert_eq!(reparse_block(&old, &edit).is_some(), incremental);
+    }
+
+    /// Returns the errors ordered by position, as reparsing does not report them in the order
+    /// parsing from scratch finds them.
+    fn sorted_errors(parse: &Parse) -> Vec<ParseError> {
+        let mut errors = parse.errors().to_vec();
+        errors.sort_by_key(|error| (error.span.start(), error.span.end()));
+        errors
+    }
+
+    #[test]
+    fn test_edit_inside_block() {
+        let text = "function foo() -> Integer { a + b }\nfunction bar() -> Integer { 1 }";
+        check(text, TextEdit::replace(Span::new(32, 33), "c * d"), true);
+    }
+
+    #[test]
+    fn test_edit_inside_nested_block() {
+        let text = "function foo() { { a; } b }";
+        check(text, TextEdit::insert(20, " + 1"), true);
+    }
+
+    #[test]
+    fn test_errors_are_shifted() {
+        let text = "function foo() { a }\nlet x: Integer = 1";
+        check(text, TextEdit::insert(18, "b; "), true);
+    }
+
+    #[test]
+    fn test_new_errors() {
+        let text = "function foo() { a }\nfunction bar() { b c }";
+        check(text, TextEdit::insert(18, "+"), true);
+    }
+
+    #[test]
+    fn test_unclosed_after_block_is_shifted() {
+        let text = "function foo() { a }\nclass Bar { let x: T;";
+        check(text, TextEdit::insert(18, " + b"), true);
+    }
+
+    #[test]
+    fn test_block_inside_parentheses() {
+        let text = "let x: T = f({ a });";
+        check(text, TextEdit::insert(16, " )"), false);
+    }
+
+    #[test]
+    fn test_removed_brace() {
+        let text = "function foo() { { a } }";
+        check(text, TextEdit::delete(Span::new(21, 22)), false);
+    }
+
+    #[test]
+    fn test_added_brace() {
+        let text = "function foo() { a }";
+        check(text, TextEdit::insert(17, "} {"), false);
+    }
+
+    #[test]
+    fn test_edit_outside_block() {
+        let text = "function foo() { a }";
+        check(text, TextEdit::replace(Span::new(9, 12), "bar"), false);
+    }
+
+    #[test]
+    fn test_unchanged_siblings_are_shared() {
+        let text = "function foo() { a }\nfunction bar() { b }";
+        let old = parse(text);
+        let new = old.reparse(&TextEdit::insert(18, "+ c"));
+        let function = |parse: &Parse| match &parse.green().children()[2] {
+            GreenElement::Node(node) => node.clone(),
+            GreenElement::Token(_) => panic!("expected a node"),
+        };
+        assert!(std::ptr::eq(function(&old).children(), function(&new).children()));
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn test_reparse_matches_parse(
+            inserted in "([a-z1 ;+{}()]){0,4}",
+            start in 0..80usize,
+            length in 0..4usize,
+        ) {
+            let text = "function foo() -> Integer { let x: T = { a + 1 }; f(x); { b } }\nlet y: T = { c };";
+            let start = start.min(text.len());
+            let span = Span::new(start, (start + length).min(text.len()));
+            let edit = TextEdit::replace(span, inserted);
+            let new = parse(text).reparse(&edit);
+            let expected = parse(&edit.apply(text));
+            proptest::prop_assert_eq!(new.green(), expected.green());
+            proptest::prop_assert_eq!(sorted_errors(&new), sorted_errors(&expected));
+        }
+    }
+}
@@ -0,0 +1,651 @@
+//! The red tree: a view of the green tree with positions and parents.
+//!
+//! Red nodes are created on demand while navigating and point to their green node, so creating
+//! and cloning them is cheap.
+
+use std::fmt;
+use std::iter;
+use std::rc::Rc;
+use super::TokenKind;
+use super::tree::{GreenElement, GreenNode, GreenToken, SyntaxKind};
+use crate::span::Span;
+
+/// A composite node in the concrete syntax tree, which knows its position and its parent.
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<SyntaxNodeData>);
+
+struct SyntaxNodeData {
+    green: GreenNode,
+    parent: Option<SyntaxNode>,
+    /// The index of this node in the children of its parent.
+    index: usize,
+    offset: usize,
+}
+
+/// A leaf in the concrete syntax tree, which knows its position and its parent.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SyntaxToken {
+    green: GreenToken,
+    parent: SyntaxNode,
+    index: usize,
+    offset: usize,
+}
+
+/// A child in the concrete syntax tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    /// Create the root of a tree.
+    pub fn new_root(green: GreenNode) -> Self {
+        Self(Rc::new(SyntaxNodeData {
+            green,
+            parent: None,
+            index: 0,
+            offset: 0,
+        }))
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind()
+    }
+
+    pub fn span(&self) -> Span {
+        Span::new(self.0.offset, self.0.offset + self.0.green.length())
+    }
+
+    pub fn green(&self) -> &GreenNode {
+        &self.0.green
+    }
+
+    /// Returns the text of all tokens in this node, including trivia.
+    ///
+    /// The text of the root of a parsed tree is identical to the source code it was parsed from.
+    pub fn text(&self) -> String {
+        self.0.green.text()
+    }
+
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.clone()
+    }
+
+    /// Returns the index of this node in the children of its parent.
+    pub fn index(&self) -> usize {
+        self.0.index
+    }
+
+    /// Returns a readable representation of this node and everything inside it, with one node or
+    /// token per line, indented by its depth:
+    ///
+    /// ```text
+    /// Module@0..11
+    ///   Keyword(Module)@0..6 "module"
+    ///   Whitespace@6..7 " "
+    ///   Identifier@7..10 "foo"
+    ///   Semicolon@10..11 ";"
+    /// ```
+    ///
+    /// This is also the alternate `Debug` representation (`{:#?}`).
+    pub fn dump(&self) -> String {
+        let mut output = String::new();
+        self.dump_into(0, &mut output);
+        output
+    }
+
+    fn dump_into(&self, depth: usize, output: &mut String) {
+        output.push_str(&format!("{}{:?}\n", "  ".repeat(depth), self));
+        for child in self.children_with_tokens() {
+            match child {
+                SyntaxElement::Node(node) => node.dump_into(depth + 1, output),
+                SyntaxElement::Token(token) => output.push_str(&format!("{}{:?}\n", "  ".repeat(depth + 1), token)),
+            }
+        }
+    }
+
+    /// Returns this node and everything inside it as JSON, so that tools which do not use this
+    /// crate can inspect the tree.
+    ///
+    /// A node is an object with its `kind`, its `span` and its `children`. A token is an object
+    /// with its `kind`, its `span` and its `text`. Kinds are written like the `serde`
+    /// representation of a [`TokenKind`]: the name of the variant, or an object from the name of
+    /// the variant to its value. Spans are objects with a `start` and an `end` byte offset:
+    ///
+    /// ```text
+    /// {"kind":"Module","span":{"start":0,"end":10},"children":[
+    ///   {"kind":{"Keyword":"Module"},"span":{"start":0,"end":6},"text":"module"},
+    ///   ...
+    /// ]}
+    /// ```
+    pub fn to_json(&self) -> String {
+        let mut output = String::new();
+        self.write_json(&mut output);
+        output
+    }
+
+    fn write_json(&self, output: &mut String) {
+        output.push_str(&format!("{{\"kind\":\"{:?}\",\"span\":", self.kind()));
+        write_json_span(self.span(), output);
+        output.push_str(",\"children\":[");
+        for (index, child) in self.children_with_tokens().enumerate() {
+            if index > 0 {
+                output.push(',');
+            }
+            match child {
+                SyntaxElement::Node(node) => node.write_json(output),
+                SyntaxElement::Token(token) => {
+                    output.push_str("{\"kind\":");
+                    write_json_token_kind(token.kind(), output);
+                    output.push_str(",\"span\":");
+                    write_json_span(token.span(), output);
+                    output.push_str(",\"text\":");
+                    write_json_string(token.text(), output);
+                    output.push('}');
+                }
+            }
+        }
+        output.push_str("]}");
+    }
+
+    /// Returns the child nodes and tokens of this node, in order.
+    pub fn children_with_tokens(&self) -> impl Iterator<Item=SyntaxElement> + use<> {
+        let parent = self.clone();
+        let mut offset = self.0.offset;
+        (0..self.0.green.children().len()).map(move |index| {
+            let child = &parent.0.green.children()[index];
+            let element = parent.child(index, offset, child);
+            offset += child.length();
+            element
+        })
+    }
+
+    /// Returns the child nodes of this node, in order.
+    pub fn children(&self) -> impl Iterator<Item=SyntaxNode> + use<> {
+        self.children_with_tokens().filter_map(SyntaxElement::into_node)
+    }
+
+    pub fn first_child(&self) -> Option<SyntaxNode> {
+        self.children().next()
+    }
+
+    /// Returns this node and its ancestors, from the innermost to the root.
+    pub fn ancestors(&self) -> impl Iterator<Item=SyntaxNode> + use<> {
+        iter::successors(Some(self.clone()), SyntaxNode::parent)
+    }
+
+    /// Returns this node and all nodes inside it, in preorder.
+    pub fn descendants(&self) -> impl Iterator<Item=SyntaxNode> + use<> {
+        self.preorder().filter_map(|event| match event {
+            WalkEvent::Enter(node) => Some(node),
+            WalkEvent::Leave(_) => None,
+        })
+    }
+
+    /// Returns the next node with the same parent, skipping tokens.
+    pub fn next_sibling(&self) -> Option<SyntaxNode> {
+        let parent = self.parent()?;
+        let mut offset = self.0.offset + self.0.green.length();
+        for (index, child) in parent.0.green.children().iter().enumerate().skip(self.0.index + 1) {
+            if let GreenElement::Node(_) = child {
+                return parent.child(index, offset, child).into_node();
+            }
+            offset += child.length();
+        }
+        None
+    }
+
+    /// Returns the previous node with the same parent, skipping tokens.
+    pub fn prev_sibling(&self) -> Option<SyntaxNode> {
+        let parent = self.parent()?;
+        let mut offset = self.0.offset;
+        for (index, child) in parent.0.green.children()[..self.0.index].iter().enumerate().rev() {
+            offset -= child.length();
+            if let GreenElement::Node(_) = child {
+                return parent.child(index, offset, child).into_node();
+            }
+        }
+        None
+    }
+
+    /// Returns the token containing the offset, which must be inside this node.
+    ///
+    /// At the boundary between two tokens, this is the token starting at the offset. At the end of
+    /// the source code, this is the end of file token.
+    pub fn token_at_offset(&self, offset: usize) -> Option<SyntaxToken> {
+        let mut node = self.clone();
+        loop {
+            let child = node.children_with_tokens()
+                .find(|child| child.span().contains(offset))
+                .or_else(|| node.children_with_tokens().find(|child| {
+                    child.as_token().is_some() && child.span() == Span::empty(offset)
+                }))?;
+            match child {
+                SyntaxElement::Node(child) => node = child,
+                SyntaxElement::Token(token) => return Some(token),
+            }
+        }
+    }
+
+    /// Returns the innermost node which contains the whole span, or `None` if this node does not
+    /// contain it.
+    pub fn covering_node(&self, span: Span) -> Option<SyntaxNode> {
+        if !self.span().contains_span(span) {
+            return None;
+        }
+        let mut node = self.clone();
+        while let Some(child) = node.children().find(|child| child.span().contains_span(span)) {
+            node = child;
+        }
+        Some(node)
+    }
+
+    /// Walks through this node and all nodes inside it, entering each node before the nodes
+    /// inside it and leaving it after them.
+    pub fn preorder(&self) -> Preorder {
+        Preorder {
+            root: self.clone(),
+            next: Some(WalkEvent::Enter(self.clone())),
+        }
+    }
+
+    fn child(&self, index: usize, offset: usize, child: &GreenElement) -> SyntaxElement {
+        match child {
+            GreenElement::Node(green) => SyntaxElement::Node(Self(Rc::new(SyntaxNodeData {
+                green: green.clone(),
+                parent: Some(self.clone()),
+                index,
+                offset,
+            }))),
+            GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                green: green.clone(),
+                parent: self.clone(),
+                index,
+                offset,
+            }),
+        }
+    }
+}
+
+/// Nodes are equal if they are the same node at the same position of equal trees.
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.offset == other.0.offset
+            && GreenNode::eq(&self.0.green, &other.0.green)
+            && self.0.parent == other.0.parent
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+impl fmt::Debug for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            write!(f, "{}", self.dump().trim_end())
+        } else {
+            write!(f, "{:?}@{:?}", self.kind(), self.span().range())
+        }
+    }
+}
+
+/// An event of walking through a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WalkEvent<T> {
+    Enter(T),
+    Leave(T),
+}
+
+/// A preorder walk through a tree, see [`SyntaxNode::preorder`].
+#[derive(Debug, Clone)]
+pub struct Preorder {
+    root: SyntaxNode,
+    next: Option<WalkEvent<SyntaxNode>>,
+}
+
+impl Iterator for Preorder {
+    type Item = WalkEvent<SyntaxNode>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.next.take()?;
+        self.next = match &event {
+            WalkEvent::Enter(node) => match node.first_child() {
+                Some(child) => Some(WalkEvent::Enter(child)),
+                None => Some(WalkEvent::Leave(node.clone())),
+            },
+            WalkEvent::Leave(node) if Rc::ptr_eq(&node.0, &self.root.0) => None,
+            WalkEvent::Leave(node) => match node.next_sibling() {
+                Some(sibling) => Some(WalkEvent::Enter(sibling)),
+                None => node.parent().map(WalkEvent::Leave),
+            },
+        };
+        Some(event)
+    }
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> TokenKind {
+        self.green.kind()
+    }
+
+    pub fn text(&self) -> &str {
+        self.green.text()
+    }
+
+    pub fn span(&self) -> Span {
+        Span::new(self.offset, self.offset + self.green.length())
+    }
+
+    pub fn green(&self) -> &GreenToken {
+        &self.green
+    }
+
+    pub fn parent(&self) -> SyntaxNode {
+        self.parent.clone()
+    }
+
+    /// Returns the index of this token in the children of its parent.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl fmt::Debug for SyntaxToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}@{:?} {:?}", self.kind(), self.span().range(), self.text())
+    }
+}
+
+impl SyntaxElement {
+    pub fn span(&self) -> Span {
+        match self {
+            SyntaxElement::Node(node) => node.span(),
+            SyntaxElement::Token(token) => token.span(),
+        }
+    }
+
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        match self {
+            SyntaxElement::Node(node) => node.parent(),
+            SyntaxElement::Token(token) => Some(token.parent()),
+        }
+    }
+
+    pub fn into_node(self) -> Option<SyntaxNode> {
+        match self {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        }
+    }
+
+    pub fn as_node(&self) -> Option<&SyntaxNode> {
+        match self {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        }
+    }
+
+    pub fn as_token(&self) -> Option<&SyntaxToken> {
+        match self {
+            SyntaxElement::Node(_) => None,
+            SyntaxElement::Token(token) => Some(token),
+        }
+    }
+}
+
+fn write_json_token_kind(kind: TokenKind, output: &mut String) {
+    match kind {
+        TokenKind::Keyword(keyword) => output.push_str(&format!("{{\"Keyword\":\"{:?}\"}}", keyword)),
+        TokenKind::Error(error) => output.push_str(&format!("{{\"Error\":\"{:?}\"}}", error)),
+        kind => output.push_str(&format!("\"{:?}\"", kind)),
+    }
+}
+
+fn write_json_span(span: Span, output: &mut String) {
+    output.push_str(&format!("{{\"start\":{},\"end\":{}}}", span.start(), span.end()));
+}
+
+fn write_json_string(text: &str, output: &mut String) {
+    output.push('"');
+    for char in text.chars() {
+        match char {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            char if char.is_control() => output.push_str(&format!("\\u{:04x}", char as u32)),
+            char => output.push(char),
+        }
+    }
+    output.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::KeywordKind;
+    use crate::cst::parser::parse;
+
+    fn token(kind: TokenKind, text: &str) -> GreenElement {
+        GreenElement::Token(GreenToken::new(kind, text))
+    }
+
+    fn tree() -> SyntaxNode {
+        let module = GreenNode::new(SyntaxKind::Module, vec![
+            token(TokenKind::Keyword(KeywordKind::Module), "module"),
+            token(TokenKind::Whitespace, " "),
+            token(TokenKind::Identifier, "foo"),
+            token(TokenKind::Semicolon, ";"),
+        ]);
+        SyntaxNode::new_root(GreenNode::new(SyntaxKind::File, vec![
+            token(TokenKind::Whitespace, "\n"),
+            GreenElement::Node(module),
+            token(TokenKind::EndOfFile, ""),
+        ]))
+    }
+
+    #[test]
+    fn test_spans() {
+        let root = tree();
+        assert_eq!(root.span(), Span::new(0, "\nmodule foo;".len()));
+        let children = root.children_with_tokens().collect::<Vec<_>>();
+        assert_eq!(children.iter().map(SyntaxElement::span).collect::<Vec<_>>(), [
+            Span::new(0, 1),
+            Span::new(1, 12),
+            Span::empty(12),
+        ]);
+        let module = children[1].as_node().unwrap();
+        let name = module.children_with_tokens().nth(2).unwrap();
+        assert_eq!(name.as_token().map(SyntaxToken::text), Some("foo"));
+        assert_eq!(name.span(), Span::new(8, 11));
+    }
+
+    #[test]
+    fn test_text() {
+        let root = tree();
+        assert_eq!(root.text(), "\nmodule foo;");
+        let module = root.children_with_tokens().nth(1).unwrap();
+        assert_eq!(module.as_node().unwrap().text(), "module foo;");
+    }
+
+    #[test]
+    fn test_dump() {
+        let expected = r#"
+File@0..12
+  Whitespace@0..1 "\n"
+  Module@1..12
+    Keyword(Module)@1..7 "module"
+    Whitespace@7..8 " "
+    Identifier@8..11 "foo"
+    Semicolon@11..12 ";"
+  EndOfFile@12..12 ""
+"#;
+        assert_eq!(tree().dump(), expected.trim_start());
+        assert_eq!(format!("{:#?}", tree()), expected.trim());
+        assert_eq!(format!("{:?}", tree()), "File@0..12");
+    }
+
+    #[test]
+    fn test_to_json() {
+        let module = tree().first_child().unwrap();
+        assert_eq!(module.to_json(), concat!(
+            r#"{"kind":"Module","span":{"start":1,"end":12},"children":["#,
+            r#"{"kind":{"Keyword":"Module"},"span":{"start":1,"end":7},"text":"module"},"#,
+            r#"{"kind":"Whitespace","span":{"start":7,"end":8},"text":" "},"#,
+            r#"{"kind":"Identifier","span":{"start":8,"end":11},"text":"foo"},"#,
+            r#"{"kind":"Semicolon","span":{"start":11,"end":12},"text":";"}"#,
+            "]}",
+        ));
+    }
+
+    #[test]
+    fn test_to_json_is_valid() {
+        let text = "let x: T = r#\"a \"quoted\" \\ text\"#;\r\n\t\u{7}";
+        let json = parse(text).syntax().to_json();
+        let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        fn collect(value: &serde_json::Value, output: &mut String) {
+            match value.get("children") {
+                Some(children) => children.as_array().unwrap().iter().for_each(|child| collect(child, output)),
+                None => output.push_str(value["text"].as_str().unwrap()),
+            }
+        }
+        let mut output = String::new();
+        collect(&value, &mut output);
+        assert_eq!(output, text);
+        assert_eq!(value["span"]["end"], text.len());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_to_json_token_kinds_match_serde() {
+        fn check(node: &SyntaxNode, json: &serde_json::Value) {
+            for (child, json) in node.children_with_tokens().zip(json["children"].as_array().unwrap()) {
+                match child {
+                    SyntaxElement::Node(node) => check(&node, json),
+                    SyntaxElement::Token(token) => assert_eq!(json["kind"], serde_json::to_value(token.kind()).unwrap()),
+                }
+            }
+        }
+        let root = parse("let x = 1a; §").syntax();
+        check(&root, &serde_json::from_str(&root.to_json()).unwrap());
+    }
+
+    #[test]
+    fn test_parent() {
+        let root = tree();
+        let module = root.children_with_tokens().nth(1).unwrap();
+        let module = module.as_node().unwrap();
+        assert_eq!(module.parent(), Some(root.clone()));
+        assert_eq!(module.index(), 1);
+        let keyword = module.children_with_tokens().next().unwrap();
+        assert_eq!(keyword.parent().as_ref(), Some(module));
+        assert_eq!(root.parent(), None);
+    }
+
+    /// Returns the kinds of the nodes, to compare traversals.
+    fn kinds(nodes: impl Iterator<Item=SyntaxNode>) -> Vec<SyntaxKind> {
+        nodes.map(|node| node.kind()).collect()
+    }
+
+    #[test]
+    fn test_children_and_descendants() {
+        let root = parse("class Foo { let x: T = 1; }\nmodule bar;").syntax();
+        assert_eq!(kinds(root.children()), [SyntaxKind::Class, SyntaxKind::Module]);
+        assert_eq!(kinds(root.descendants()), [
+            SyntaxKind::File,
+            SyntaxKind::Class,
+            SyntaxKind::Field,
+            SyntaxKind::Type,
+            SyntaxKind::PathExpression,
+            SyntaxKind::LiteralExpression,
+            SyntaxKind::Module,
+        ]);
+    }
+
+    #[test]
+    fn test_ancestors() {
+        let root = parse("class Foo { let x: T = 1; }").syntax();
+        let path = root.descendants().find(|node| node.kind() == SyntaxKind::PathExpression).unwrap();
+        assert_eq!(kinds(path.ancestors()), [
+            SyntaxKind::PathExpression,
+            SyntaxKind::Type,
+            SyntaxKind::Field,
+            SyntaxKind::Class,
+            SyntaxKind::File,
+        ]);
+    }
+
+    #[test]
+    fn test_siblings() {
+        let root = parse("module a; class B {} module c;").syntax();
+        let class = root.children().nth(1).unwrap();
+        let next = class.next_sibling().unwrap();
+        let previous = class.prev_sibling().unwrap();
+        assert_eq!(next.text(), "module c;");
+        assert_eq!(next.span(), Span::new(21, 30));
+        assert_eq!(previous.text(), "module a;");
+        assert_eq!(previous.span(), Span::new(0, 9));
+        assert_eq!(previous.prev_sibling(), None);
+        assert_eq!(next.next_sibling(), None);
+        assert_eq!(next.prev_sibling(), Some(class));
+    }
+
+    #[test]
+    fn test_preorder() {
+        let root = parse("let x: T = 1;").syntax();
+        let field = root.first_child().unwrap();
+        let events = field.preorder()
+            .map(|event| match event {
+                WalkEvent::Enter(node) => format!("enter {:?}", node.kind()),
+                WalkEvent::Leave(node) => format!("leave {:?}", node.kind()),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(events, [
+            "enter Field",
+            "enter Type",
+            "enter PathExpression",
+            "leave PathExpression",
+            "leave Type",
+            "enter LiteralExpression",
+            "leave LiteralExpression",
+            "leave Field",
+        ]);
+    }
+
+    #[test]
+    fn test_token_at_offset() {
+        let text = "let x: T = a + 1;";
+        let root = parse(text).syntax();
+        let token = |offset| root.token_at_offset(offset).map(|token| (token.kind(), token.text().to_string()));
+        assert_eq!(token(0), Some((TokenKind::Keyword(KeywordKind::Field), "let".to_string())));
+        assert_eq!(token(3), Some((TokenKind::Whitespace, " ".to_string())));
+        assert_eq!(token(11), Some((TokenKind::Identifier, "a".to_string())));
+        assert_eq!(token(12), Some((TokenKind::Whitespace, " ".to_string())));
+        assert_eq!(token(text.len()), Some((TokenKind::EndOfFile, String::new())));
+        assert_eq!(token(text.len() + 1), None);
+        let literal = root.token_at_offset(15).unwrap();
+        assert_eq!(literal.parent().kind(), SyntaxKind::LiteralExpression);
+    }
+
+    #[test]
+    fn test_covering_node() {
+        let root = parse("let x: T = a + 1;").syntax();
+        let kind = |span| root.covering_node(span).map(|node| node.kind());
+        assert_eq!(kind(Span::new(11, 12)), Some(SyntaxKind::PathExpression));
+        assert_eq!(kind(Span::new(11, 14)), Some(SyntaxKind::BinaryExpression));
+        assert_eq!(kind(Span::new(4, 12)), Some(SyntaxKind::Field));
+        assert_eq!(kind(Span::new(0, 17)), Some(SyntaxKind::Field));
+        assert_eq!(kind(Span::new(0, 18)), None);
+    }
+
+    #[test]
+    fn test_equality() {
+        let root = tree();
+        let first = root.children_with_tokens().nth(1).unwrap();
+        let second = root.children_with_tokens().nth(1).unwrap();
+        assert_eq!(first, second);
+        assert_ne!(Some(&root), first.as_node());
+        assert_eq!(root, tree());
+    }
+}
@@ -3,22 +3,109 @@ use crate::cst::Span;
 
 /// A token is a character or sequence in the source code of some associated type.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Token<'text> {
     pub(super) kind: TokenKind,
-    pub(super) span: Span<'text>,
+    pub(super) span: Span,
+    pub(super) text: &'text str,
+}
+
+impl<'text> Token<'text> {
+    pub fn kind(self) -> TokenKind {
+        self.kind
+    }
+
+    pub fn span(self) -> Span {
+        self.span
+    }
+
+    /// Returns the text of this token.
+    pub fn text(self) -> &'text str {
+        self.text
+    }
+
+    /// Returns the keyword this token can be interpreted as, if it is an identifier spelling a
+    /// contextual keyword.
+    pub fn contextual_keyword(self) -> Option<KeywordKind> {
+        if self.kind != TokenKind::Identifier {
+            return None;
+        }
+        KeywordKind::try_from(self.text())
+            .ok()
+            .filter(|keyword| keyword.is_contextual())
+    }
+
+    /// Check if this token can be interpreted as the given kind.
+    ///
+    /// An identifier spelling a contextual keyword can be interpreted as that keyword.
+    pub fn is(self, kind: TokenKind) -> bool {
+        self.kind == kind || match kind {
+            TokenKind::Keyword(keyword) => self.contextual_keyword() == Some(keyword),
+            _ => false
+        }
+    }
+
+    /// Copy the text of this token, so that it can outlive the source code.
+    pub fn into_owned(self) -> OwnedToken {
+        OwnedToken::from(self)
+    }
+}
+
+/// A token which owns its text.
+///
+/// Unlike [`Token`], it can outlive the source code and be deserialized from any input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedToken {
+    pub kind: TokenKind,
+    pub span: Span,
+    pub text: String,
+}
+
+impl OwnedToken {
+    /// Returns a token borrowing the text of this token.
+    pub fn as_ref(&self) -> Token<'_> {
+        Token {
+            kind: self.kind,
+            span: self.span,
+            text: &self.text,
+        }
+    }
+}
+
+impl From<Token<'_>> for OwnedToken {
+    fn from(token: Token<'_>) -> Self {
+        Self {
+            kind: token.kind,
+            span: token.span,
+            text: token.text.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TokenKind {
     /// Any sequence of whitespace.
     Whitespace,
 
+    /// An interpreter directive on the first line, e.g. `#!/usr/bin/env zinc`.
+    Shebang,
+
+    /// A UTF-8 byte order mark at the very start of the source code.
+    ByteOrderMark,
+
     /// An identifier.
     Identifier,
 
     /// An integer literal.
     Integer,
 
+    /// A raw string literal, e.g. `r"C:\path"` or `r#"say "hi""#`.
+    ///
+    /// Escape sequences are not processed in raw strings.
+    RawString,
+
     /// Any keyword.
     Keyword(KeywordKind),
 
@@ -31,16 +118,50 @@ pub enum TokenKind {
     /// `=`
     Equals,
 
-    // We technically don't use '-' token yet.
-    // However, they are used to construct '->'.
     // The lexer shouldn't combine tokens since it doesn't know whether the syntax expects the
     // tokens individually or combined. As a result, it's easier to return a '-' token which can
     // be combined with the '>' token during parsing.
 
+    /// `+`
+    Plus,
     /// `-`
     Minus,
+    /// `*`
+    Asterisk,
+    /// `/`
+    Slash,
+    /// `%`
+    Percent,
+    /// `!`
+    Exclamation,
+    /// `&`
+    Ampersand,
+    /// `|`
+    Pipe,
+    /// `^`
+    Caret,
+    /// `~`
+    Tilde,
+    /// `<`
+    LessThan,
     /// `>`
     GreaterThan,
+    /// `==`
+    DoubleEquals,
+    /// `!=`
+    NotEquals,
+    /// `<=`
+    LessThanOrEquals,
+    /// `>=`
+    GreaterThanOrEquals,
+    /// `&&`
+    DoubleAmpersand,
+    /// `||`
+    DoublePipe,
+    /// `<<`
+    LeftShift,
+    /// `>>`
+    RightShift,
     /// `->`
     RightArrow,
     /// `::`
@@ -54,24 +175,174 @@ pub enum TokenKind {
     LeftParentheses,
     /// `)`
     RightParentheses,
+    /// `[`
+    LeftBracket,
+    /// `]`
+    RightBracket,
+
+    // Reserved characters which are not used by the syntax yet. Lexing them as their own tokens
+    // allows the parser to report them as reserved rather than unexpected.
+
+    /// `?`
+    Question,
+    /// `@`
+    At,
+    /// `#`
+    Hash,
+    /// `$`
+    Dollar,
+    /// `\`
+    Backslash,
+    /// `` ` ``
+    Backtick,
 
-    /// Any unknown character.
-    Unknown,
+    /// The end of the source code. Always empty.
+    EndOfFile,
+
+    /// Source code that could not be lexed into a valid token.
+    Error(LexErrorKind),
 }
 
 impl TokenKind {
+    /// Check if this token carries no meaning for the syntax, such as whitespace.
+    pub fn is_trivia(self) -> bool {
+        matches!(self, TokenKind::Whitespace | TokenKind::Shebang | TokenKind::ByteOrderMark)
+    }
+
+    pub fn is_keyword(self) -> bool {
+        matches!(self, TokenKind::Keyword(_))
+    }
+
+    /// Check if this token is a literal value, including the literal keywords (e.g. `true`).
+    pub fn is_literal(self) -> bool {
+        match self {
+            TokenKind::Integer | TokenKind::RawString => true,
+            TokenKind::Keyword(keyword) => keyword.is_literal(),
+            _ => false
+        }
+    }
+
+    /// Check if this token is an operator, a separator, or a delimiter.
+    pub fn is_punctuation(self) -> bool {
+        self.symbol().is_some()
+    }
+
+    /// Check if this token is built up of other tokens (e.g. `->`).
+    pub fn is_combined(self) -> bool {
+        self.decompose().len() > 1
+    }
+
+    pub fn is_error(self) -> bool {
+        matches!(self, TokenKind::Error(_))
+    }
+
+    pub fn is_opening_delimiter(self) -> bool {
+        self.matching_close().is_some()
+    }
+
+    pub fn is_closing_delimiter(self) -> bool {
+        self.matching_open().is_some()
+    }
+
+    /// Returns the closing delimiter matching this opening delimiter.
+    pub fn matching_close(self) -> Option<TokenKind> {
+        match self {
+            TokenKind::LeftBrace => Some(TokenKind::RightBrace),
+            TokenKind::LeftParentheses => Some(TokenKind::RightParentheses),
+            TokenKind::LeftBracket => Some(TokenKind::RightBracket),
+            _ => None
+        }
+    }
+
+    /// Returns the opening delimiter matching this closing delimiter.
+    pub fn matching_open(self) -> Option<TokenKind> {
+        match self {
+            TokenKind::RightBrace => Some(TokenKind::LeftBrace),
+            TokenKind::RightParentheses => Some(TokenKind::LeftParentheses),
+            TokenKind::RightBracket => Some(TokenKind::LeftBracket),
+            _ => None
+        }
+    }
+
+    /// Returns the text of a punctuation token, which is always spelled the same way.
+    pub fn symbol(self) -> Option<&'static str> {
+        match self.spelling() {
+            Spelling::Symbol(symbol) => Some(symbol),
+            _ => None
+        }
+    }
+
+    /// Returns how this kind is written in messages, which is the text for punctuation.
+    fn spelling(self) -> Spelling {
+        match self {
+            TokenKind::Whitespace => Spelling::Name("whitespace"),
+            TokenKind::Shebang => Spelling::Name("shebang"),
+            TokenKind::ByteOrderMark => Spelling::Name("byte order mark"),
+            TokenKind::Identifier => Spelling::Name("identifier"),
+            TokenKind::Integer => Spelling::Name("integer"),
+            TokenKind::RawString => Spelling::Name("raw string"),
+            TokenKind::Keyword(keyword) => Spelling::Keyword(keyword),
+            TokenKind::EndOfFile => Spelling::Name("end of file"),
+            TokenKind::Error(error) => Spelling::Error(error),
+            TokenKind::Comma => Spelling::Symbol(","),
+            TokenKind::Semicolon => Spelling::Symbol(";"),
+            TokenKind::Colon => Spelling::Symbol(":"),
+            TokenKind::Equals => Spelling::Symbol("="),
+            TokenKind::Plus => Spelling::Symbol("+"),
+            TokenKind::Minus => Spelling::Symbol("-"),
+            TokenKind::Asterisk => Spelling::Symbol("*"),
+            TokenKind::Slash => Spelling::Symbol("/"),
+            TokenKind::Percent => Spelling::Symbol("%"),
+            TokenKind::Exclamation => Spelling::Symbol("!"),
+            TokenKind::Ampersand => Spelling::Symbol("&"),
+            TokenKind::Pipe => Spelling::Symbol("|"),
+            TokenKind::Caret => Spelling::Symbol("^"),
+            TokenKind::Tilde => Spelling::Symbol("~"),
+            TokenKind::LessThan => Spelling::Symbol("<"),
+            TokenKind::GreaterThan => Spelling::Symbol(">"),
+            TokenKind::DoubleEquals => Spelling::Symbol("=="),
+            TokenKind::NotEquals => Spelling::Symbol("!="),
+            TokenKind::LessThanOrEquals => Spelling::Symbol("<="),
+            TokenKind::GreaterThanOrEquals => Spelling::Symbol(">="),
+            TokenKind::DoubleAmpersand => Spelling::Symbol("&&"),
+            TokenKind::DoublePipe => Spelling::Symbol("||"),
+            TokenKind::LeftShift => Spelling::Symbol("<<"),
+            TokenKind::RightShift => Spelling::Symbol(">>"),
+            TokenKind::RightArrow => Spelling::Symbol("->"),
+            TokenKind::PathSeparator => Spelling::Symbol("::"),
+            TokenKind::LeftBrace => Spelling::Symbol("{"),
+            TokenKind::RightBrace => Spelling::Symbol("}"),
+            TokenKind::LeftParentheses => Spelling::Symbol("("),
+            TokenKind::RightParentheses => Spelling::Symbol(")"),
+            TokenKind::LeftBracket => Spelling::Symbol("["),
+            TokenKind::RightBracket => Spelling::Symbol("]"),
+            TokenKind::Question => Spelling::Symbol("?"),
+            TokenKind::At => Spelling::Symbol("@"),
+            TokenKind::Hash => Spelling::Symbol("#"),
+            TokenKind::Dollar => Spelling::Symbol("$"),
+            TokenKind::Backslash => Spelling::Symbol("\\"),
+            TokenKind::Backtick => Spelling::Symbol("`"),
+        }
+    }
+
     /// Try to combine a list of consecutive tokens into a new token of this type.
     pub fn combine<'text>(self, parts: &[Token<'text>]) -> Option<Token<'text>> {
-        let expected = self.decompose().into_iter();
-        let actual = parts.iter()
-            .map(|token| token.kind);
-        if expected.eq(actual) {
+        let expected = self.decompose();
+        let matches = expected.len() == parts.len() && expected.into_iter()
+            .zip(parts)
+            .all(|(kind, token)| token.is(kind));
+        if matches {
             let spans = parts.iter()
                 .map(|token| token.span);
             let span = Span::combine(spans)?;
+            let text = match parts {
+                [part] => part.text,
+                _ => self.symbol()?
+            };
             Some(Token {
                 kind: self,
                 span,
+                text,
             })
         } else {
             None
@@ -81,6 +352,14 @@ impl TokenKind {
     /// Returns all parts that make up this token.
     pub fn decompose(self) -> Vec<TokenKind> {
         match self {
+            TokenKind::DoubleEquals => vec![TokenKind::Equals, TokenKind::Equals],
+            TokenKind::NotEquals => vec![TokenKind::Exclamation, TokenKind::Equals],
+            TokenKind::LessThanOrEquals => vec![TokenKind::LessThan, TokenKind::Equals],
+            TokenKind::GreaterThanOrEquals => vec![TokenKind::GreaterThan, TokenKind::Equals],
+            TokenKind::DoubleAmpersand => vec![TokenKind::Ampersand, TokenKind::Ampersand],
+            TokenKind::DoublePipe => vec![TokenKind::Pipe, TokenKind::Pipe],
+            TokenKind::LeftShift => vec![TokenKind::LessThan, TokenKind::LessThan],
+            TokenKind::RightShift => vec![TokenKind::GreaterThan, TokenKind::GreaterThan],
             TokenKind::RightArrow => vec![TokenKind::Minus, TokenKind::GreaterThan],
             TokenKind::PathSeparator => vec![TokenKind::Colon, TokenKind::Colon],
             _ => vec![self]
@@ -89,37 +368,63 @@ impl TokenKind {
 }
 
 impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.spelling() {
+            Spelling::Symbol(text) | Spelling::Name(text) => write!(f, "{}", text),
+            Spelling::Keyword(keyword) => write!(f, "{}", keyword),
+            Spelling::Error(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// How a token kind is written: punctuation as its symbol, and other kinds by name.
+enum Spelling {
+    Symbol(&'static str),
+    Name(&'static str),
+    Keyword(KeywordKind),
+    Error(LexErrorKind),
+}
+
+/// The reason some source code could not be lexed into a valid token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LexErrorKind {
+    /// A character that does not start any token.
+    UnexpectedCharacter,
+    /// A string literal without a closing delimiter.
+    ///
+    /// The span starts at the opening delimiter and runs until the end of the source code.
+    UnterminatedString,
+    /// An integer literal followed by characters that are not digits, e.g. `123abc`.
+    InvalidDigit,
+}
+
+impl fmt::Display for LexErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", match self {
-            TokenKind::Whitespace => "whitespace",
-            TokenKind::Identifier => "identifier",
-            TokenKind::Integer => "integer",
-            TokenKind::Keyword(keyword) => {
-                return write!(f, "{}", keyword)
-            },
-            TokenKind::Comma => ",",
-            TokenKind::Semicolon => ";",
-            TokenKind::Colon => ":",
-            TokenKind::Equals => "=",
-            TokenKind::Minus => "-",
-            TokenKind::GreaterThan => ">",
-            TokenKind::RightArrow => "->",
-            TokenKind::PathSeparator => "::",
-            TokenKind::LeftBrace => "{",
-            TokenKind::RightBrace => "}",
-            TokenKind::LeftParentheses => "(",
-            TokenKind::RightParentheses => ")",
-            TokenKind::Unknown => "unknown"
+            LexErrorKind::UnexpectedCharacter => "unexpected character",
+            LexErrorKind::UnterminatedString => "unterminated string",
+            LexErrorKind::InvalidDigit => "invalid digit"
         })
     }
 }
 
+/// A keyword is an identifier reserved by the language.
+///
+/// A contextual keyword is only reserved where the syntax expects it. Elsewhere it is an ordinary
+/// identifier. The lexer returns contextual keywords as identifiers and the parser asks for the
+/// keyword interpretation through [`TokenKind::combine`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum KeywordKind {
     /// `module`
     Module,
     /// `class`
     Class,
+    /// `enum`
+    Enum,
+    /// `struct`
+    Struct,
     /// `let`
     Field,
     /// `function`
@@ -128,34 +433,154 @@ pub enum KeywordKind {
     Constant,
     /// `mutable`
     Mutable,
+    /// `public`
+    Public,
+    /// `private`
+    Private,
+    /// `if`
+    If,
+    /// `else`
+    Else,
+    /// `while`
+    While,
+    /// `for`
+    For,
+    /// `in`
+    In,
+    /// `match`
+    Match,
+    /// `return`
+    Return,
+    /// `break`
+    Break,
+    /// `continue`
+    Continue,
+    /// `true`
+    True,
+    /// `false`
+    False,
+    /// `none`
+    None,
+}
+
+impl KeywordKind {
+    pub const ALL: [KeywordKind; 22] = [
+        KeywordKind::Module, KeywordKind::Class, KeywordKind::Enum, KeywordKind::Struct,
+        KeywordKind::Field, KeywordKind::Function, KeywordKind::Constant, KeywordKind::Mutable,
+        KeywordKind::Public, KeywordKind::Private, KeywordKind::If, KeywordKind::Else,
+        KeywordKind::While, KeywordKind::For, KeywordKind::In, KeywordKind::Match,
+        KeywordKind::Return, KeywordKind::Break, KeywordKind::Continue, KeywordKind::True,
+        KeywordKind::False, KeywordKind::None,
+    ];
+
+    /// Check if this keyword is a literal value.
+    pub fn is_literal(self) -> bool {
+        matches!(self, KeywordKind::True | KeywordKind::False | KeywordKind::None)
+    }
+
+    /// Check if this keyword is only reserved where the syntax expects it.
+    pub fn is_contextual(self) -> bool {
+        matches!(self, KeywordKind::Constant | KeywordKind::Mutable | KeywordKind::Public | KeywordKind::Private)
+    }
+
+    /// Returns how this keyword is spelled in the source code.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            KeywordKind::Module => "module",
+            KeywordKind::Class => "class",
+            KeywordKind::Enum => "enum",
+            KeywordKind::Struct => "struct",
+            KeywordKind::Field => "let",
+            KeywordKind::Function => "function",
+            KeywordKind::Constant => "constant",
+            KeywordKind::Mutable => "mutable",
+            KeywordKind::Public => "public",
+            KeywordKind::Private => "private",
+            KeywordKind::If => "if",
+            KeywordKind::Else => "else",
+            KeywordKind::While => "while",
+            KeywordKind::For => "for",
+            KeywordKind::In => "in",
+            KeywordKind::Match => "match",
+            KeywordKind::Return => "return",
+            KeywordKind::Break => "break",
+            KeywordKind::Continue => "continue",
+            KeywordKind::True => "true",
+            KeywordKind::False => "false",
+            KeywordKind::None => "none"
+        }
+    }
+}
+
+/// The length of the longest keyword.
+const MAX_KEYWORD_LENGTH: usize = {
+    let mut max = 0;
+    let mut index = 0;
+    while index < KeywordKind::ALL.len() {
+        let length = KeywordKind::ALL[index].as_str().len();
+        if length > max {
+            max = length;
+        }
+        index += 1;
+    }
+    max
+};
+
+/// Keywords grouped by the length of their spelling, so that an identifier is only compared
+/// against keywords of the same length.
+///
+/// The keywords of length `n` are `keywords[starts[n]..starts[n + 1]]`. Both are derived from
+/// [`KeywordKind::ALL`] and [`KeywordKind::as_str`] when compiling.
+struct KeywordsByLength {
+    keywords: [KeywordKind; KeywordKind::ALL.len()],
+    starts: [usize; MAX_KEYWORD_LENGTH + 2],
+}
+
+static KEYWORDS_BY_LENGTH: KeywordsByLength = {
+    let mut keywords = KeywordKind::ALL;
+    let mut starts = [0; MAX_KEYWORD_LENGTH + 2];
+    let mut count = 0;
+    let mut length = 0;
+    while length <= MAX_KEYWORD_LENGTH {
+        starts[length] = count;
+        let mut index = 0;
+        while index < KeywordKind::ALL.len() {
+            if KeywordKind::ALL[index].as_str().len() == length {
+                keywords[count] = KeywordKind::ALL[index];
+                count += 1;
+            }
+            index += 1;
+        }
+        length += 1;
+    }
+    starts[MAX_KEYWORD_LENGTH + 1] = count;
+    KeywordsByLength { keywords, starts }
+};
+
+impl KeywordsByLength {
+    /// Returns the keywords spelled with the given number of bytes.
+    fn get(&self, length: usize) -> &[KeywordKind] {
+        if length > MAX_KEYWORD_LENGTH {
+            return &[];
+        }
+        &self.keywords[self.starts[length]..self.starts[length + 1]]
+    }
 }
 
 impl TryFrom<&str> for KeywordKind {
     type Error = ();
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        match value {
-            "module" => Ok(KeywordKind::Module),
-            "class" => Ok(KeywordKind::Class),
-            "let" => Ok(KeywordKind::Field),
-            "function" => Ok(KeywordKind::Function),
-            "constant" => Ok(KeywordKind::Constant),
-            "mutable" => Ok(KeywordKind::Mutable),
-            _ => Err(())
-        }
+        KEYWORDS_BY_LENGTH.get(value.len()).iter()
+            .copied()
+            .find(|keyword| keyword.as_str() == value)
+            .ok_or(())
     }
 }
 
 impl fmt::Display for KeywordKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", match self {
-            KeywordKind::Module => "module",
-            KeywordKind::Class => "class",
-            KeywordKind::Field => "let",
-            KeywordKind::Function => "function",
-            KeywordKind::Constant => "constant",
-            KeywordKind::Mutable => "mutable"
-        })
+        write!(f, "{}", self.as_str())
     }
 }
 
@@ -163,34 +588,184 @@ impl fmt::Display for KeywordKind {
 mod tests {
     use super::*;
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serialize_token() {
+        let token = Token { kind: TokenKind::Keyword(KeywordKind::Class), span: Span::new(4, 9), text: "class" };
+        let json = serde_json::to_string(&token).unwrap();
+        assert_eq!(json, r#"{"kind":{"Keyword":"Class"},"span":{"start":4,"end":9},"text":"class"}"#);
+        assert_eq!(serde_json::from_str::<Token>(&json).unwrap(), token);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialize_owned_token() {
+        // Escaped text cannot be borrowed from the input, so it requires an owned token.
+        let json = r#"{"kind":"Whitespace","span":{"start":0,"end":1},"text":"\n"}"#;
+        assert!(serde_json::from_str::<Token>(json).is_err());
+        let token = serde_json::from_str::<OwnedToken>(json).unwrap();
+        assert_eq!(token, OwnedToken { kind: TokenKind::Whitespace, span: Span::new(0, 1), text: "\n".to_string() });
+    }
+
+    #[test]
+    fn test_owned_token_round_trip() {
+        let owned = {
+            let text = String::from("foo");
+            Token { kind: TokenKind::Identifier, span: Span::new(0, 3), text: &text }.into_owned()
+        };
+        assert_eq!(owned, OwnedToken { kind: TokenKind::Identifier, span: Span::new(0, 3), text: "foo".to_string() });
+        assert_eq!(owned.as_ref(), Token { kind: TokenKind::Identifier, span: Span::new(0, 3), text: "foo" });
+        assert!(owned.as_ref().is(TokenKind::Identifier));
+    }
+
+    #[test]
+    fn test_classification() {
+        assert!(TokenKind::Whitespace.is_trivia());
+        assert!(TokenKind::Shebang.is_trivia());
+        assert!(TokenKind::ByteOrderMark.is_trivia());
+        assert!(!TokenKind::EndOfFile.is_trivia());
+        assert!(TokenKind::Keyword(KeywordKind::Class).is_keyword());
+        assert!(!TokenKind::Identifier.is_keyword());
+        assert!(TokenKind::Integer.is_literal());
+        assert!(TokenKind::RawString.is_literal());
+        assert!(TokenKind::Keyword(KeywordKind::True).is_literal());
+        assert!(!TokenKind::Keyword(KeywordKind::Class).is_literal());
+        assert!(TokenKind::Comma.is_punctuation());
+        assert!(TokenKind::RightArrow.is_punctuation());
+        assert!(TokenKind::LeftBrace.is_punctuation());
+        assert!(!TokenKind::Identifier.is_punctuation());
+        assert!(TokenKind::Hash.is_punctuation());
+        assert!(TokenKind::PathSeparator.is_combined());
+        assert!(!TokenKind::Colon.is_combined());
+        assert!(TokenKind::Error(LexErrorKind::InvalidDigit).is_error());
+    }
+
+    #[test]
+    fn test_matching_delimiter() {
+        for (open, close) in [
+            (TokenKind::LeftBrace, TokenKind::RightBrace),
+            (TokenKind::LeftParentheses, TokenKind::RightParentheses),
+            (TokenKind::LeftBracket, TokenKind::RightBracket),
+        ] {
+            assert!(open.is_opening_delimiter());
+            assert!(close.is_closing_delimiter());
+            assert_eq!(open.matching_close(), Some(close));
+            assert_eq!(close.matching_open(), Some(open));
+        }
+        assert!(!TokenKind::LessThan.is_opening_delimiter());
+        assert_eq!(TokenKind::RightBrace.matching_close(), None);
+    }
+
+    #[test]
+    fn test_keyword_display_round_trip() {
+        for keyword in KeywordKind::ALL {
+            assert_eq!(KeywordKind::try_from(keyword.to_string().as_str()), Ok(keyword));
+        }
+        assert_eq!(KeywordKind::try_from("elif"), Err(()));
+        assert_eq!(KeywordKind::try_from(""), Err(()));
+        assert_eq!(KeywordKind::try_from("functions"), Err(()));
+    }
+
+    #[test]
+    fn test_keywords_by_length() {
+        for length in 0..=MAX_KEYWORD_LENGTH + 1 {
+            let expected = KeywordKind::ALL.into_iter()
+                .filter(|keyword| keyword.as_str().len() == length)
+                .collect::<Vec<_>>();
+            assert_eq!(KEYWORDS_BY_LENGTH.get(length), expected);
+        }
+        assert_eq!(KEYWORDS_BY_LENGTH.get(usize::MAX), []);
+    }
+
+    #[test]
+    fn test_combine_contextual_keyword() {
+        let parts = [
+            Token { kind: TokenKind::Identifier, span: Span::new(0, 7), text: "mutable" },
+        ];
+        let result = TokenKind::Keyword(KeywordKind::Mutable).combine(&parts);
+        assert_eq!(result, Some(Token { kind: TokenKind::Keyword(KeywordKind::Mutable), span: Span::new(0, 7), text: "mutable" }));
+        assert_eq!(TokenKind::Keyword(KeywordKind::Constant).combine(&parts), None);
+        assert_eq!(TokenKind::Identifier.combine(&parts), Some(parts[0]));
+    }
+
+    #[test]
+    fn test_combine_reserved_keyword_from_identifier() {
+        let parts = [
+            Token { kind: TokenKind::Identifier, span: Span::new(0, 5), text: "class" },
+        ];
+        let result = TokenKind::Keyword(KeywordKind::Class).combine(&parts);
+        assert_eq!(result, None);
+    }
+
     #[test]
     fn test_combine_right_arrow() {
-        let text = "->";
         let parts = vec![
-            Token { kind: TokenKind::Minus, span: Span { text, start_offset: 0, length: 1 } },
-            Token { kind: TokenKind::GreaterThan, span: Span { text, start_offset: 1, length: 1 } },
+            Token { kind: TokenKind::Minus, span: Span::new(0, 1), text: "-" },
+            Token { kind: TokenKind::GreaterThan, span: Span::new(1, 2), text: ">" },
         ];
         let result = TokenKind::RightArrow.combine(&parts);
-        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span { text, start_offset: 0, length: 2 } }));
+        assert_eq!(result, Some(Token { kind: TokenKind::RightArrow, span: Span::new(0, 2), text: "->" }));
     }
 
     #[test]
     fn test_combine_path_separator() {
-        let text = "::";
         let parts = vec![
-            Token { kind: TokenKind::Colon, span: Span { text, start_offset: 0, length: 1 } },
-            Token { kind: TokenKind::Colon, span: Span { text, start_offset: 1, length: 1 } },
+            Token { kind: TokenKind::Colon, span: Span::new(0, 1), text: ":" },
+            Token { kind: TokenKind::Colon, span: Span::new(1, 2), text: ":" },
         ];
         let result = TokenKind::PathSeparator.combine(&parts);
-        assert_eq!(result, Some(Token { kind: TokenKind::PathSeparator, span: Span { text, start_offset: 0, length: 2 } }));
+        assert_eq!(result, Some(Token { kind: TokenKind::PathSeparator, span: Span::new(0, 2), text: "::" }));
+    }
+
+    #[test]
+    fn test_combine_comparison() {
+        let parts = [
+            Token { kind: TokenKind::Equals, span: Span::new(0, 1), text: "=" },
+            Token { kind: TokenKind::Equals, span: Span::new(1, 2), text: "=" },
+            Token { kind: TokenKind::Exclamation, span: Span::new(2, 3), text: "!" },
+            Token { kind: TokenKind::Equals, span: Span::new(3, 4), text: "=" },
+            Token { kind: TokenKind::LessThan, span: Span::new(4, 5), text: "<" },
+            Token { kind: TokenKind::Equals, span: Span::new(5, 6), text: "=" },
+            Token { kind: TokenKind::GreaterThan, span: Span::new(6, 7), text: ">" },
+            Token { kind: TokenKind::Equals, span: Span::new(7, 8), text: "=" },
+        ];
+        assert_eq!(TokenKind::DoubleEquals.combine(&parts[0..2]), Some(Token { kind: TokenKind::DoubleEquals, span: Span::new(0, 2), text: "==" }));
+        assert_eq!(TokenKind::NotEquals.combine(&parts[2..4]), Some(Token { kind: TokenKind::NotEquals, span: Span::new(2, 4), text: "!=" }));
+        assert_eq!(TokenKind::LessThanOrEquals.combine(&parts[4..6]), Some(Token { kind: TokenKind::LessThanOrEquals, span: Span::new(4, 6), text: "<=" }));
+        assert_eq!(TokenKind::GreaterThanOrEquals.combine(&parts[6..8]), Some(Token { kind: TokenKind::GreaterThanOrEquals, span: Span::new(6, 8), text: ">=" }));
+    }
+
+    #[test]
+    fn test_combine_logical() {
+        let parts = [
+            Token { kind: TokenKind::Ampersand, span: Span::new(0, 1), text: "&" },
+            Token { kind: TokenKind::Ampersand, span: Span::new(1, 2), text: "&" },
+            Token { kind: TokenKind::Pipe, span: Span::new(2, 3), text: "|" },
+            Token { kind: TokenKind::Pipe, span: Span::new(3, 4), text: "|" },
+        ];
+        assert_eq!(TokenKind::DoubleAmpersand.combine(&parts[0..2]), Some(Token { kind: TokenKind::DoubleAmpersand, span: Span::new(0, 2), text: "&&" }));
+        assert_eq!(TokenKind::DoublePipe.combine(&parts[2..4]), Some(Token { kind: TokenKind::DoublePipe, span: Span::new(2, 4), text: "||" }));
+        assert_eq!(TokenKind::DoubleAmpersand.combine(&parts[1..3]), None);
+    }
+
+    #[test]
+    fn test_combine_shift() {
+        let parts = [
+            Token { kind: TokenKind::LessThan, span: Span::new(0, 1), text: "<" },
+            Token { kind: TokenKind::LessThan, span: Span::new(1, 2), text: "<" },
+            Token { kind: TokenKind::GreaterThan, span: Span::new(2, 3), text: ">" },
+            Token { kind: TokenKind::GreaterThan, span: Span::new(3, 4), text: ">" },
+        ];
+        assert_eq!(TokenKind::LeftShift.combine(&parts[0..2]), Some(Token { kind: TokenKind::LeftShift, span: Span::new(0, 2), text: "<<" }));
+        assert_eq!(TokenKind::RightShift.combine(&parts[2..4]), Some(Token { kind: TokenKind::RightShift, span: Span::new(2, 4), text: ">>" }));
+        assert_eq!(TokenKind::RightShift.combine(&parts[1..3]), None);
     }
 
     #[test]
     fn test_combine_wrong_parts() {
-        let text = "-;";
         let parts = vec![
-            Token { kind: TokenKind::Minus, span: Span { text, start_offset: 0, length: 1 } },
-            Token { kind: TokenKind::Semicolon, span: Span { text, start_offset: 1, length: 1 } },
+            Token { kind: TokenKind::Minus, span: Span::new(0, 1), text: "-" },
+            Token { kind: TokenKind::Semicolon, span: Span::new(1, 2), text: ";" },
         ];
         let result = TokenKind::RightArrow.combine(&parts);
         assert_eq!(result, None);
@@ -198,10 +773,9 @@ mod tests {
 
     #[test]
     fn test_combine_non_consecutive() {
-        let text = "- >";
         let parts = vec![
-            Token { kind: TokenKind::Minus, span: Span { text, start_offset: 0, length: 1 } },
-            Token { kind: TokenKind::GreaterThan, span: Span { text, start_offset: 2, length: 1 } },
+            Token { kind: TokenKind::Minus, span: Span::new(0, 1), text: "-" },
+            Token { kind: TokenKind::GreaterThan, span: Span::new(2, 3), text: ">" },
         ];
         let result = TokenKind::RightArrow.combine(&parts);
         assert_eq!(result, None);
@@ -209,19 +783,17 @@ mod tests {
 
     #[test]
     fn test_combine_single_token() {
-        let text = ",";
         let parts = vec![
-            Token { kind: TokenKind::Comma, span: Span { text, start_offset: 0, length: 1 } },
+            Token { kind: TokenKind::Comma, span: Span::new(0, 1), text: "," },
         ];
         let result = TokenKind::Comma.combine(&parts);
-        assert_eq!(result, Some(Token { kind: TokenKind::Comma, span: Span { text, start_offset: 0, length: 1 } }));
+        assert_eq!(result, Some(Token { kind: TokenKind::Comma, span: Span::new(0, 1), text: "," }));
     }
 
     #[test]
     fn test_combine_insufficient_parts() {
-        let text = "-";
         let parts = vec![
-            Token { kind: TokenKind::Minus, span: Span { text, start_offset: 0, length: 1 } },
+            Token { kind: TokenKind::Minus, span: Span::new(0, 1), text: "-" },
         ];
         let result = TokenKind::RightArrow.combine(&parts);
         assert_eq!(result, None);
@@ -1,40 +1,207 @@
-use crate::cst::token::Token;
+//! The green tree: an immutable concrete syntax tree (CST) which owns its text.
+//!
+//! Green nodes only know their kind, their length, and their children, not their position in the
+//! source code or their parent. This makes them cheap to clone and lets an unchanged subtree be
+//! shared between the trees of two versions of the source code. [`super::syntax`] wraps a green
+//! tree to navigate it with positions and parents.
 
-/// A concrete syntax tree (CST).
-///
-/// A tree is a one-to-one representation of some object in the source code.
+use std::sync::Arc;
+use crate::cst::token::TokenKind;
+
+/// A composite node in the green tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GreenNode(Arc<GreenNodeData>);
+
+#[derive(Debug, PartialEq, Eq)]
+struct GreenNodeData {
+    kind: SyntaxKind,
+    length: usize,
+    children: Box<[GreenElement]>,
+}
+
+/// A leaf in the green tree.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Tree<'text> {
-    pub(super) kind: TreeKind,
-    pub(super) children: Vec<Node<'text>>,
+pub struct GreenToken(Arc<GreenTokenData>);
+
+#[derive(Debug, PartialEq, Eq)]
+struct GreenTokenData {
+    kind: TokenKind,
+    text: Box<str>,
 }
 
-/// A node in a tree.
-/// A node is either a leaf node (a token) or a composite node (a tree).
+/// A child in the green tree.
+/// A child is either a leaf node (a token) or a composite node (a tree).
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Node<'text> {
-    Tree(Tree<'text>),
-    Token(Token<'text>),
+pub enum GreenElement {
+    Node(GreenNode),
+    Token(GreenToken),
+}
+
+impl GreenNode {
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        let length = children.iter()
+            .map(GreenElement::length)
+            .sum();
+        Self(Arc::new(GreenNodeData {
+            kind,
+            length,
+            children: children.into_boxed_slice(),
+        }))
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.kind
+    }
+
+    /// Returns the length in bytes of the text this node covers.
+    pub fn length(&self) -> usize {
+        self.0.length
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.0.children
+    }
+
+    /// Returns a copy of this node with the child at the given index replaced. The other children
+    /// are shared with this node.
+    pub fn replace_child(&self, index: usize, child: GreenElement) -> GreenNode {
+        let mut children = self.children().to_vec();
+        children[index] = child;
+        GreenNode::new(self.kind(), children)
+    }
+
+    /// Returns the text this node covers.
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.length());
+        self.write_text(&mut text);
+        text
+    }
+
+    fn write_text(&self, output: &mut String) {
+        for child in self.children() {
+            match child {
+                GreenElement::Node(node) => node.write_text(output),
+                GreenElement::Token(token) => output.push_str(token.text()),
+            }
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum TreeKind {
+impl GreenToken {
+    pub fn new(kind: TokenKind, text: &str) -> Self {
+        Self(Arc::new(GreenTokenData {
+            kind,
+            text: text.into(),
+        }))
+    }
+
+    pub fn kind(&self) -> TokenKind {
+        self.0.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.0.text
+    }
+
+    /// Returns the length in bytes of the text of this token.
+    pub fn length(&self) -> usize {
+        self.0.text.len()
+    }
+}
+
+impl GreenElement {
+    pub fn length(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.length(),
+            GreenElement::Token(token) => token.length(),
+        }
+    }
+}
+
+/// The kind of a composite node in the syntax tree. Tokens have a [`TokenKind`] instead.
+///
+/// [`super::ast`] provides typed wrappers for most kinds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxKind {
     File,
     Module,
+    Attribute,
     Class,
+    Enum,
+    Variant,
+    Struct,
+    StructField,
     Function,
     Field,
     Inherits,
     Parameters,
     Parameter,
+    GenericParameters,
+    GenericParameter,
     Type,
+    GenericArguments,
     Expression,
     LiteralExpression,
     PrefixExpression,
+    BinaryExpression,
     PathExpression,
     CallExpression,
     Arguments,
     ParenthesizedExpression,
     BlockExpression,
-    Statement
-}
\ No newline at end of file
+    IfExpression,
+    ForLoop,
+    MatchExpression,
+    MatchArm,
+    BindingPattern,
+    WildcardPattern,
+    LiteralPattern,
+    Statement,
+    ReturnStatement,
+    /// Tokens which could not be parsed.
+    Error,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::KeywordKind;
+
+    #[test]
+    fn test_length() {
+        let name = GreenToken::new(TokenKind::Identifier, "foo");
+        let node = GreenNode::new(SyntaxKind::Module, vec![
+            GreenElement::Token(GreenToken::new(TokenKind::Keyword(KeywordKind::Module), "module")),
+            GreenElement::Token(GreenToken::new(TokenKind::Whitespace, " ")),
+            GreenElement::Token(name.clone()),
+        ]);
+        assert_eq!(name.length(), 3);
+        assert_eq!(node.length(), "module foo".len());
+        assert_eq!(GreenNode::new(SyntaxKind::File, vec![GreenElement::Node(node)]).length(), 10);
+    }
+
+    #[test]
+    fn test_replace_child() {
+        let name = GreenElement::Token(GreenToken::new(TokenKind::Identifier, "foo"));
+        let keyword = GreenNode::new(SyntaxKind::Type, vec![
+            GreenElement::Token(GreenToken::new(TokenKind::Keyword(KeywordKind::Module), "module")),
+        ]);
+        let node = GreenNode::new(SyntaxKind::Module, vec![GreenElement::Node(keyword.clone()), name]);
+        let replaced = node.replace_child(1, GreenElement::Token(GreenToken::new(TokenKind::Identifier, "bar")));
+        assert_eq!(replaced.text(), "modulebar");
+        assert_eq!(replaced.length(), 9);
+        let GreenElement::Node(shared) = &replaced.children()[0] else {
+            panic!("expected a node");
+        };
+        assert!(std::ptr::eq(shared.children(), keyword.children()));
+    }
+
+    #[test]
+    fn test_clone_shares_children() {
+        let node = GreenNode::new(SyntaxKind::Module, vec![
+            GreenElement::Token(GreenToken::new(TokenKind::Keyword(KeywordKind::Module), "module")),
+        ]);
+        let clone = node.clone();
+        assert!(std::ptr::eq(node.children(), clone.children()));
+    }
+}
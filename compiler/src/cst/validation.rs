@@ -0,0 +1,228 @@
+//! Checks of rules which the grammar accepts but the language does not, such as a return outside
+//! of a function.
+//!
+//! These rules depend on the context of a node, so checking them after parsing keeps the parser
+//! simple. The parser also stays more forgiving this way, as the tree is complete even if it
+//! breaks one of these rules.
+
+use std::fmt;
+use super::{KeywordKind, TokenKind};
+use super::syntax::{SyntaxNode, SyntaxToken};
+use super::tree::SyntaxKind;
+use crate::span::Span;
+
+/// Check the rules which the parser does not check in a parsed tree.
+pub fn validate(root: &SyntaxNode) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    for node in root.descendants() {
+        match node.kind() {
+            SyntaxKind::ReturnStatement => validate_return(&node, &mut errors),
+            SyntaxKind::Field => {
+                validate_visibility(&node, &mut errors);
+                validate_item_modifiers(&node, &mut errors);
+                validate_field(&node, &mut errors);
+                validate_mutability(&node, &mut errors);
+            }
+            SyntaxKind::Parameter | SyntaxKind::StructField => validate_mutability(&node, &mut errors),
+            SyntaxKind::Module | SyntaxKind::Class | SyntaxKind::Enum | SyntaxKind::Struct |
+            SyntaxKind::Function => {
+                validate_visibility(&node, &mut errors);
+                validate_item_modifiers(&node, &mut errors);
+            }
+            _ => {}
+        }
+    }
+    errors
+}
+
+/// An error found while validating a tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationError {
+    pub kind: ValidationErrorKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    ReturnOutsideFunction,
+    /// A field declared directly in a file or a module, instead of in a class or a block.
+    FieldOutsideClass,
+    /// A modifier before a declaration which cannot have it, such as a mutable function.
+    ModifierNotAllowed(KeywordKind),
+    /// A field or a parameter which is both constant and mutable.
+    ConstantMutable,
+    /// A visibility after the first one of a declaration, such as `private` in
+    /// `public private class Foo {}`.
+    DuplicateVisibility,
+}
+
+impl fmt::Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationErrorKind::ReturnOutsideFunction => write!(f, "return outside of a function"),
+            ValidationErrorKind::FieldOutsideClass => write!(f, "field outside of a class"),
+            ValidationErrorKind::ModifierNotAllowed(keyword) => write!(f, "{} is not allowed on this declaration", keyword),
+            ValidationErrorKind::ConstantMutable => write!(f, "constant and mutable cannot be combined"),
+            ValidationErrorKind::DuplicateVisibility => write!(f, "visibility is declared more than once"),
+        }
+    }
+}
+
+/// A return must be inside the body of a function.
+fn validate_return(node: &SyntaxNode, errors: &mut Vec<ValidationError>) {
+    let in_function = node.ancestors().any(|ancestor| ancestor.kind() == SyntaxKind::Function);
+    if !in_function {
+        errors.push(ValidationError {
+            kind: ValidationErrorKind::ReturnOutsideFunction,
+            span: node.span(),
+        });
+    }
+}
+
+/// A field must be declared in a class, or as a statement in a block.
+fn validate_field(node: &SyntaxNode, errors: &mut Vec<ValidationError>) {
+    let outside_class = node.parent()
+        .is_some_and(|parent| matches!(parent.kind(), SyntaxKind::File | SyntaxKind::Module));
+    if outside_class {
+        errors.push(ValidationError {
+            kind: ValidationErrorKind::FieldOutsideClass,
+            span: node.span(),
+        });
+    }
+}
+
+/// A declaration can only be declared public or private once.
+fn validate_visibility(node: &SyntaxNode, errors: &mut Vec<ValidationError>) {
+    let visibilities = tokens(node)
+        .take_while(|token| !is_declaration_keyword(token.kind()))
+        .filter(|token| matches!(token.kind(), TokenKind::Keyword(KeywordKind::Public | KeywordKind::Private)));
+    for visibility in visibilities.skip(1) {
+        errors.push(ValidationError {
+            kind: ValidationErrorKind::DuplicateVisibility,
+            span: visibility.span(),
+        });
+    }
+}
+
+/// Only a function can be declared constant, and no declaration can be declared mutable. The
+/// modifiers of a field after `let` are checked by [`validate_mutability`] instead.
+fn validate_item_modifiers(node: &SyntaxNode, errors: &mut Vec<ValidationError>) {
+    let modifiers = tokens(node)
+        .take_while(|token| !is_declaration_keyword(token.kind()));
+    for modifier in modifiers {
+        let TokenKind::Keyword(keyword) = modifier.kind() else {
+            continue;
+        };
+        let allowed = match keyword {
+            KeywordKind::Constant => node.kind() == SyntaxKind::Function,
+            KeywordKind::Mutable => false,
+            _ => continue
+        };
+        if !allowed {
+            errors.push(ValidationError {
+                kind: ValidationErrorKind::ModifierNotAllowed(keyword),
+                span: modifier.span(),
+            });
+        }
+    }
+}
+
+/// A field or a parameter cannot be both constant and mutable. Only the modifiers of a field after
+/// `let` are part of its mutability.
+fn validate_mutability(node: &SyntaxNode, errors: &mut Vec<ValidationError>) {
+    let mut modifiers = tokens(node);
+    if node.kind() == SyntaxKind::Field {
+        modifiers.find(|token| token.kind() == TokenKind::Keyword(KeywordKind::Field));
+    }
+    let modifiers = modifiers.map(|token| token.kind()).collect::<Vec<_>>();
+    let has = |keyword| modifiers.contains(&TokenKind::Keyword(keyword));
+    if has(KeywordKind::Constant) && has(KeywordKind::Mutable) {
+        errors.push(ValidationError {
+            kind: ValidationErrorKind::ConstantMutable,
+            span: node.span(),
+        });
+    }
+}
+
+/// The keywords a declaration starts with, after its attributes and modifiers.
+fn is_declaration_keyword(kind: TokenKind) -> bool {
+    matches!(kind, TokenKind::Keyword(
+        KeywordKind::Module | KeywordKind::Class | KeywordKind::Enum | KeywordKind::Struct |
+        KeywordKind::Function | KeywordKind::Field
+    ))
+}
+
+/// Returns the child tokens of the node.
+fn tokens(node: &SyntaxNode) -> impl Iterator<Item=SyntaxToken> + use<> {
+    node.children_with_tokens().filter_map(|child| child.as_token().cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::parser::parse;
+
+    fn check(text: &str, errors: &[ValidationError]) {
+        let parse = parse(text);
+        assert_eq!(parse.errors(), []);
+        assert_eq!(validate(&parse.syntax()), errors);
+    }
+
+    #[test]
+    fn test_return_in_function() {
+        check("function foo() -> Integer { if a { return 1; } 2 }", &[]);
+    }
+
+    #[test]
+    fn test_return_outside_function() {
+        check("class Foo { let x: Integer = { return 1; }; }", &[ValidationError {
+            kind: ValidationErrorKind::ReturnOutsideFunction,
+            span: Span::new(31, 40),
+        }]);
+    }
+
+    #[test]
+    fn test_field_outside_class() {
+        check("let x: Integer = 1;\nmodule foo { let y: Integer; }", &[
+            ValidationError { kind: ValidationErrorKind::FieldOutsideClass, span: Span::new(0, 19) },
+            ValidationError { kind: ValidationErrorKind::FieldOutsideClass, span: Span::new(33, 48) },
+        ]);
+    }
+
+    #[test]
+    fn test_field_in_class_and_block() {
+        check("class Foo { let x: Integer; function f() { let y: Integer = 1; } }", &[]);
+    }
+
+    #[test]
+    fn test_modifiers() {
+        check("class Foo { constant function f() {} mutable function g() {} }", &[ValidationError {
+            kind: ValidationErrorKind::ModifierNotAllowed(KeywordKind::Mutable),
+            span: Span::new(37, 44),
+        }]);
+        check("public constant class Foo {}", &[ValidationError {
+            kind: ValidationErrorKind::ModifierNotAllowed(KeywordKind::Constant),
+            span: Span::new(7, 15),
+        }]);
+    }
+
+    #[test]
+    fn test_duplicate_visibility() {
+        check("public private class Foo { public public let x: Integer; private function f() {} }", &[
+            ValidationError { kind: ValidationErrorKind::DuplicateVisibility, span: Span::new(7, 14) },
+            ValidationError { kind: ValidationErrorKind::DuplicateVisibility, span: Span::new(34, 40) },
+        ]);
+    }
+
+    #[test]
+    fn test_constant_mutable() {
+        check("class Foo(constant mutable x: Integer) { let mutable constant y: Integer; }", &[
+            ValidationError { kind: ValidationErrorKind::ConstantMutable, span: Span::new(10, 37) },
+            ValidationError { kind: ValidationErrorKind::ConstantMutable, span: Span::new(41, 73) },
+        ]);
+        check("class Foo { constant let mutable x: Integer; }", &[ValidationError {
+            kind: ValidationErrorKind::ModifierNotAllowed(KeywordKind::Constant),
+            span: Span::new(12, 20),
+        }]);
+    }
+}
@@ -1,4 +1,9 @@
-mod cst;
+pub mod cst;
+pub mod line_index;
+pub mod session;
+pub mod source_map;
+pub mod span;
+pub mod text_edit;
 
 pub fn add(left: u64, right: u64) -> u64 {
     left + right
@@ -0,0 +1,166 @@
+//! Mapping between offsets in the source code and line/column positions.
+
+/// A zero-based line and column in the source code.
+///
+/// The column is the byte offset from the start of the line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LineColumn {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// An index of the lines in some source code.
+///
+/// Built once from the source code, it converts between offsets and line/column positions in
+/// logarithmic time. A line ends at `\n`, `\r\n`, or a lone `\r`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    /// The offset at which each line starts. The first line always starts at offset 0.
+    line_starts: Vec<usize>,
+    /// The offset at which each line ends, excluding the line break.
+    line_ends: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut line_ends = Vec::new();
+        let bytes = text.as_bytes();
+        let mut offset = 0;
+        while offset < bytes.len() {
+            let length = line_break_length(&bytes[offset..]);
+            if length > 0 {
+                line_ends.push(offset);
+                line_starts.push(offset + length);
+                offset += length;
+            } else {
+                offset += 1;
+            }
+        }
+        line_ends.push(text.len());
+        Self {
+            line_starts,
+            line_ends,
+        }
+    }
+
+    /// Returns the number of lines.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Converts an offset into a line/column position.
+    ///
+    /// Returns `None` if the offset is past the end of the source code.
+    pub fn line_column(&self, offset: usize) -> Option<LineColumn> {
+        if offset > self.length() {
+            return None;
+        }
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        Some(LineColumn {
+            line,
+            column: offset - self.line_starts[line],
+        })
+    }
+
+    /// Converts a line/column position into an offset.
+    ///
+    /// Returns `None` if the line does not exist or if the column is past the end of the line.
+    pub fn offset(&self, position: LineColumn) -> Option<usize> {
+        let start = *self.line_starts.get(position.line)?;
+        let end = self.line_ends[position.line];
+        let offset = start + position.column;
+        (offset <= end).then_some(offset)
+    }
+
+    fn length(&self) -> usize {
+        self.line_ends[self.line_ends.len() - 1]
+    }
+}
+
+/// Returns the length of the line break at the start of the text, or 0 if it does not start with
+/// a line break.
+fn line_break_length(text: &[u8]) -> usize {
+    match text {
+        [b'\r', b'\n', ..] => 2,
+        [b'\r' | b'\n', ..] => 1,
+        _ => 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_text() {
+        let index = LineIndex::new("");
+        assert_eq!(index.line_count(), 1);
+        assert_eq!(index.line_column(0), Some(LineColumn { line: 0, column: 0 }));
+        assert_eq!(index.line_column(1), None);
+        assert_eq!(index.offset(LineColumn { line: 0, column: 0 }), Some(0));
+    }
+
+    #[test]
+    fn test_line_column() {
+        let index = LineIndex::new("foo\nbar\n\nbaz");
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_column(0), Some(LineColumn { line: 0, column: 0 }));
+        assert_eq!(index.line_column(3), Some(LineColumn { line: 0, column: 3 }));
+        assert_eq!(index.line_column(4), Some(LineColumn { line: 1, column: 0 }));
+        assert_eq!(index.line_column(6), Some(LineColumn { line: 1, column: 2 }));
+        assert_eq!(index.line_column(8), Some(LineColumn { line: 2, column: 0 }));
+        assert_eq!(index.line_column(9), Some(LineColumn { line: 3, column: 0 }));
+        assert_eq!(index.line_column(12), Some(LineColumn { line: 3, column: 3 }));
+        assert_eq!(index.line_column(13), None);
+    }
+
+    #[test]
+    fn test_offset() {
+        let index = LineIndex::new("foo\nbar\n\nbaz");
+        assert_eq!(index.offset(LineColumn { line: 0, column: 0 }), Some(0));
+        assert_eq!(index.offset(LineColumn { line: 0, column: 3 }), Some(3));
+        assert_eq!(index.offset(LineColumn { line: 0, column: 4 }), None);
+        assert_eq!(index.offset(LineColumn { line: 1, column: 1 }), Some(5));
+        assert_eq!(index.offset(LineColumn { line: 2, column: 0 }), Some(8));
+        assert_eq!(index.offset(LineColumn { line: 2, column: 1 }), None);
+        assert_eq!(index.offset(LineColumn { line: 3, column: 3 }), Some(12));
+        assert_eq!(index.offset(LineColumn { line: 3, column: 4 }), None);
+        assert_eq!(index.offset(LineColumn { line: 4, column: 0 }), None);
+    }
+
+    #[test]
+    fn test_trailing_newline() {
+        let index = LineIndex::new("foo\n");
+        assert_eq!(index.line_count(), 2);
+        assert_eq!(index.line_column(4), Some(LineColumn { line: 1, column: 0 }));
+        assert_eq!(index.offset(LineColumn { line: 1, column: 0 }), Some(4));
+    }
+
+    #[test]
+    fn test_carriage_return() {
+        let index = LineIndex::new("foo\r\nbar\rbaz\n");
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_column(3), Some(LineColumn { line: 0, column: 3 }));
+        assert_eq!(index.line_column(5), Some(LineColumn { line: 1, column: 0 }));
+        assert_eq!(index.line_column(9), Some(LineColumn { line: 2, column: 0 }));
+        assert_eq!(index.line_column(13), Some(LineColumn { line: 3, column: 0 }));
+        assert_eq!(index.offset(LineColumn { line: 0, column: 3 }), Some(3));
+        assert_eq!(index.offset(LineColumn { line: 0, column: 4 }), None);
+        assert_eq!(index.offset(LineColumn { line: 1, column: 3 }), Some(8));
+        assert_eq!(index.offset(LineColumn { line: 1, column: 4 }), None);
+        assert_eq!(index.offset(LineColumn { line: 2, column: 3 }), Some(12));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let text = "module foo;\r\n\nclass Bar {\r    let x: Integer = 1;\n}\n";
+        let index = LineIndex::new(text);
+        // The offset between the `\r` and `\n` of a line break is not a position on the line.
+        let inside_line_break = |offset: usize| text[..offset].ends_with('\r') && text[offset..].starts_with('\n');
+        for offset in (0..=text.len()).filter(|&offset| !inside_line_break(offset)) {
+            let position = index.line_column(offset).unwrap();
+            assert_eq!(index.offset(position), Some(offset));
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! The entry point for compiling a set of source files.
+
+use std::collections::BTreeMap;
+use crate::cst::lexer::{Lexer, TokenBuffer};
+use crate::cst::parser::{self, Parse, ParseErrorKind};
+use crate::cst::token::{LexErrorKind, TokenKind};
+use crate::cst::validation::{self, ValidationError, ValidationErrorKind};
+use crate::source_map::{FileId, FileSpan, SourceFile, SourceMap};
+
+/// Collects the source files to compile together.
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    sources: SourceMap,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a source file to the session and return its identifier.
+    pub fn add_file(&mut self, name: impl Into<String>, text: impl Into<String>) -> FileId {
+        self.sources.add(SourceFile::new(name, text))
+    }
+
+    pub fn sources(&self) -> &SourceMap {
+        &self.sources
+    }
+
+    /// Lex, parse and validate every source file of the session.
+    pub fn compile(&self) -> CompilationUnit<'_> {
+        let tokens: BTreeMap<_, _> = self.sources.files()
+            .map(|(id, file)| (id, Lexer::tokenize(file.text())))
+            .collect();
+        let parses: BTreeMap<_, _> = tokens.iter()
+            .map(|(&id, tokens)| (id, parser::parse_tokens(tokens)))
+            .collect();
+        let validations = parses.iter()
+            .map(|(&id, parse)| (id, validation::validate(&parse.syntax())))
+            .collect();
+        CompilationUnit {
+            sources: &self.sources,
+            tokens,
+            parses,
+            validations,
+        }
+    }
+}
+
+/// The results of compiling the source files of a [`Session`], keyed by file.
+#[derive(Debug, Clone)]
+pub struct CompilationUnit<'session> {
+    sources: &'session SourceMap,
+    tokens: BTreeMap<FileId, TokenBuffer<'session>>,
+    parses: BTreeMap<FileId, Parse>,
+    validations: BTreeMap<FileId, Vec<ValidationError>>,
+}
+
+impl<'session> CompilationUnit<'session> {
+    pub fn sources(&self) -> &'session SourceMap {
+        self.sources
+    }
+
+    /// Returns the tokens of the given file.
+    pub fn tokens(&self, file: FileId) -> Option<&TokenBuffer<'session>> {
+        self.tokens.get(&file)
+    }
+
+    /// Returns the syntax tree of the given file.
+    pub fn parse(&self, file: FileId) -> Option<&Parse> {
+        self.parses.get(&file)
+    }
+
+    /// Returns the syntax errors of all files, ordered by file and then in the order they were
+    /// found.
+    pub fn parse_errors(&self) -> impl Iterator<Item=(FileSpan, ParseErrorKind)> + '_ {
+        self.parses.iter().flat_map(|(&file, parse)| {
+            parse.errors().iter().map(move |error| (FileSpan { file, span: error.span }, error.kind))
+        })
+    }
+
+    /// Returns the errors of all files which break a rule the parser does not check, ordered by
+    /// file and then in the order they were found.
+    pub fn validation_errors(&self) -> impl Iterator<Item=(FileSpan, ValidationErrorKind)> + '_ {
+        self.validations.iter().flat_map(|(&file, errors)| {
+            errors.iter().map(move |error| (FileSpan { file, span: error.span }, error.kind))
+        })
+    }
+
+    /// Returns the lexical errors of all files, ordered by file and position.
+    pub fn errors(&self) -> impl Iterator<Item=(FileSpan, LexErrorKind)> + '_ {
+        self.tokens.iter().flat_map(|(&file, tokens)| {
+            tokens.iter().filter_map(move |token| match token.kind() {
+                TokenKind::Error(error) => Some((FileSpan { file, span: token.span() }, error)),
+                _ => None
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cst::token::KeywordKind;
+    use crate::span::Span;
+
+    #[test]
+    fn test_compile() {
+        let mut session = Session::new();
+        let main = session.add_file("main.zn", "module main;");
+        let library = session.add_file("library.zn", "module library;\nclass Foo {}");
+        let unit = session.compile();
+        assert_eq!(unit.tokens(main).map(TokenBuffer::len), Some(5));
+        assert_eq!(unit.tokens(library).map(|tokens| tokens.source()), Some("module library;\nclass Foo {}"));
+        assert_eq!(unit.errors().count(), 0);
+        assert_eq!(unit.parse(library).map(|parse| parse.syntax().text()), Some("module library;\nclass Foo {}".to_string()));
+        assert_eq!(unit.parse_errors().count(), 0);
+        assert_eq!(unit.validation_errors().count(), 0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let mut session = Session::new();
+        let main = session.add_file("main.zn", "module main;");
+        let library = session.add_file("library.zn", "class Foo {");
+        let unit = session.compile();
+        assert_eq!(unit.parse(main).map(|parse| parse.errors().len()), Some(0));
+        assert_eq!(unit.parse_errors().collect::<Vec<_>>(), [
+            (FileSpan { file: library, span: Span::empty(11) }, ParseErrorKind::Unclosed { close: TokenKind::RightBrace, open: Span::new(10, 11) }),
+        ]);
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let mut session = Session::new();
+        let main = session.add_file("main.zn", "let x: Integer = 1;");
+        let library = session.add_file("library.zn", "class Foo { mutable function f() {} }");
+        let unit = session.compile();
+        assert_eq!(unit.parse_errors().count(), 0);
+        assert_eq!(unit.validation_errors().collect::<Vec<_>>(), [
+            (FileSpan { file: main, span: Span::new(0, 19) }, ValidationErrorKind::FieldOutsideClass),
+            (FileSpan { file: library, span: Span::new(12, 19) }, ValidationErrorKind::ModifierNotAllowed(KeywordKind::Mutable)),
+        ]);
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut session = Session::new();
+        let main = session.add_file("main.zn", "let x = 1a;");
+        let library = session.add_file("library.zn", "§ r\"");
+        let unit = session.compile();
+        assert_eq!(unit.errors().collect::<Vec<_>>(), [
+            (FileSpan { file: main, span: Span::new(8, 10) }, LexErrorKind::InvalidDigit),
+            (FileSpan { file: library, span: Span::new(0, "§".len()) }, LexErrorKind::UnexpectedCharacter),
+            (FileSpan { file: library, span: Span::new(3, 5) }, LexErrorKind::UnterminatedString),
+        ]);
+    }
+}
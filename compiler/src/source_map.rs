@@ -0,0 +1,144 @@
+//! Source files of a compilation and the mapping of spans to their file, line, and column.
+
+use crate::line_index::{LineColumn, LineIndex};
+use crate::span::Span;
+
+/// Identifies a source file in a [`SourceMap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileId(u32);
+
+/// A span in a specific source file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileSpan {
+    pub file: FileId,
+    pub span: Span,
+}
+
+/// The position of a span resolved to its file, line, and column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub file: FileId,
+    pub start: LineColumn,
+    pub end: LineColumn,
+}
+
+/// The source code of a file with its name, such as the path it was read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceFile {
+    name: String,
+    text: String,
+    lines: LineIndex,
+}
+
+impl SourceFile {
+    pub fn new(name: impl Into<String>, text: impl Into<String>) -> Self {
+        let text = text.into();
+        Self {
+            name: name.into(),
+            lines: LineIndex::new(&text),
+            text,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn lines(&self) -> &LineIndex {
+        &self.lines
+    }
+}
+
+/// Owns all source files of a compilation and assigns each of them a [`FileId`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a source file and return its identifier.
+    pub fn add(&mut self, file: SourceFile) -> FileId {
+        let id = FileId(u32::try_from(self.files.len()).expect("too many source files"));
+        self.files.push(file);
+        id
+    }
+
+    /// Returns the source file with the given identifier.
+    ///
+    /// Returns `None` if no file was added with the identifier. An identifier of another source map
+    /// is not detected: it returns the file which was added at the same position in this map.
+    pub fn get(&self, id: FileId) -> Option<&SourceFile> {
+        self.files.get(id.0 as usize)
+    }
+
+    /// Returns all source files with their identifiers, in the order they were added.
+    pub fn files(&self) -> impl Iterator<Item=(FileId, &SourceFile)> {
+        self.files.iter()
+            .enumerate()
+            .map(|(index, file)| (FileId(index as u32), file))
+    }
+
+    /// Resolves a span to the line and column at which it starts and ends.
+    ///
+    /// Returns `None` if the file does not exist or if the span is past the end of the file.
+    pub fn resolve(&self, span: FileSpan) -> Option<Location> {
+        let lines = self.get(span.file)?.lines();
+        Some(Location {
+            file: span.file,
+            start: lines.line_column(span.span.start())?,
+            end: lines.line_column(span.span.end())?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add() {
+        let mut map = SourceMap::new();
+        let main = map.add(SourceFile::new("main.zn", "module main;\n"));
+        let library = map.add(SourceFile::new("library.zn", "module library;\n"));
+        assert_ne!(main, library);
+        assert_eq!(map.get(main).map(SourceFile::name), Some("main.zn"));
+        assert_eq!(map.get(library).map(SourceFile::text), Some("module library;\n"));
+        assert_eq!(map.files().map(|(id, _)| id).collect::<Vec<_>>(), [main, library]);
+    }
+
+    #[test]
+    fn test_get_unknown_file() {
+        let mut other = SourceMap::new();
+        let first = other.add(SourceFile::new("main.zn", ""));
+        let second = other.add(SourceFile::new("library.zn", ""));
+        assert_eq!(SourceMap::new().get(first), None);
+        let mut map = SourceMap::new();
+        map.add(SourceFile::new("other.zn", ""));
+        assert_eq!(map.get(second), None);
+        assert_eq!(map.get(first).map(SourceFile::name), Some("other.zn"));
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut map = SourceMap::new();
+        map.add(SourceFile::new("main.zn", "module main;\n"));
+        let file = map.add(SourceFile::new("library.zn", "module library;\n\nclass Foo {}\n"));
+        let location = map.resolve(FileSpan { file, span: Span::new(23, 26) });
+        assert_eq!(location, Some(Location {
+            file,
+            start: LineColumn { line: 2, column: 6 },
+            end: LineColumn { line: 2, column: 9 },
+        }));
+        assert_eq!(map.resolve(FileSpan { file, span: Span::new(23, 100) }), None);
+    }
+}
@@ -0,0 +1,228 @@
+//! Positions of text in the source code.
+
+use std::ops::Range;
+
+/// A range of bytes in the source code.
+///
+/// Used by tokens, nodes in the CST, and diagnostics to reference what text in the source code
+/// they represent. Editors usually count characters or UTF-16 code units instead of bytes, see
+/// [`Span::char_range`] and [`Span::utf16_range`] for the conversions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Span {
+    start: usize,
+    end: usize,
+}
+
+impl Span {
+    /// Create a span from a start offset (inclusive) and an end offset (exclusive).
+    ///
+    /// Panics if the span starts after it ends.
+    pub fn new(start: usize, end: usize) -> Self {
+        assert!(start <= end, "span starts after it ends");
+        Self {
+            start,
+            end,
+        }
+    }
+
+    /// Create an empty span at the given offset.
+    pub fn empty(offset: usize) -> Self {
+        Self::new(offset, offset)
+    }
+
+    /// Combine a list of consecutive spans into a new span.
+    ///
+    /// Returns `None` if the iterator is empty or if the iterator is non-consecutive.
+    pub fn combine(mut spans: impl Iterator<Item=Span>) -> Option<Span> {
+        let first = spans.next()?;
+        spans.try_fold(first, |previous, next| {
+            if previous.end == next.start {
+                Some(Span::new(previous.start, next.end))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns the smallest span covering both spans.
+    pub fn merge(self, other: Span) -> Span {
+        Span::new(self.start.min(other.start), self.end.max(other.end))
+    }
+
+    /// Returns the span covered by both spans.
+    ///
+    /// Returns an empty span if the spans touch and `None` if they are disjoint.
+    pub fn intersect(self, other: Span) -> Option<Span> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        (start <= end).then(|| Span::new(start, end))
+    }
+
+    /// Check if the offset is inside this span.
+    pub fn contains(self, offset: usize) -> bool {
+        self.start <= offset && offset < self.end
+    }
+
+    /// Check if the other span is completely inside this span.
+    pub fn contains_span(self, other: Span) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    /// Returns the text this span covers in the source code.
+    pub fn text(self, source: &str) -> &str {
+        &source[self.range()]
+    }
+
+    /// Returns the range of characters this span covers in the source code.
+    pub fn char_range(self, source: &str) -> Range<usize> {
+        self.convert(source, |_| 1)
+    }
+
+    /// Returns the range of UTF-16 code units this span covers in the source code.
+    pub fn utf16_range(self, source: &str) -> Range<usize> {
+        self.convert(source, char::len_utf16)
+    }
+
+    /// Create a span from a range of characters in the source code.
+    ///
+    /// Returns `None` if the range is past the end of the source code.
+    pub fn from_char_range(source: &str, range: Range<usize>) -> Option<Span> {
+        Self::convert_back(source, range, |_| 1)
+    }
+
+    /// Create a span from a range of UTF-16 code units in the source code.
+    ///
+    /// Returns `None` if the range is past the end of the source code or splits a surrogate pair.
+    pub fn from_utf16_range(source: &str, range: Range<usize>) -> Option<Span> {
+        Self::convert_back(source, range, char::len_utf16)
+    }
+
+    /// Converts the byte offsets into offsets counted in other units.
+    fn convert(self, source: &str, units: fn(char) -> usize) -> Range<usize> {
+        let start = source[..self.start].chars().map(units).sum::<usize>();
+        let length = self.text(source).chars().map(units).sum::<usize>();
+        start..start + length
+    }
+
+    /// Converts offsets counted in other units into byte offsets.
+    fn convert_back(source: &str, range: Range<usize>, units: fn(char) -> usize) -> Option<Span> {
+        let offset = |target: usize| {
+            let mut counted = 0;
+            for (offset, next) in source.char_indices() {
+                if counted >= target {
+                    return (counted == target).then_some(offset);
+                }
+                counted += units(next);
+            }
+            (counted == target).then_some(source.len())
+        };
+        let start = offset(range.start)?;
+        let end = offset(range.end)?;
+        (start <= end).then(|| Span::new(start, end))
+    }
+
+    pub fn start(self) -> usize {
+        self.start
+    }
+
+    pub fn end(self) -> usize {
+        self.end
+    }
+
+    pub fn length(self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.start == self.end
+    }
+
+    pub fn range(self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "span starts after it ends")]
+    fn test_new_reversed() {
+        Span::new(2, 1);
+    }
+
+    #[test]
+    fn test_combine() {
+        let spans = [Span::new(0, 2), Span::new(2, 3), Span::new(3, 7)];
+        assert_eq!(Span::combine(spans.into_iter()), Some(Span::new(0, 7)));
+    }
+
+    #[test]
+    fn test_combine_non_consecutive() {
+        let spans = [Span::new(0, 2), Span::new(3, 4)];
+        assert_eq!(Span::combine(spans.into_iter()), None);
+    }
+
+    #[test]
+    fn test_combine_empty() {
+        assert_eq!(Span::combine(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn test_merge() {
+        assert_eq!(Span::new(2, 4).merge(Span::new(6, 9)), Span::new(2, 9));
+        assert_eq!(Span::new(6, 9).merge(Span::new(2, 4)), Span::new(2, 9));
+        assert_eq!(Span::new(2, 9).merge(Span::new(3, 4)), Span::new(2, 9));
+    }
+
+    #[test]
+    fn test_intersect() {
+        assert_eq!(Span::new(2, 6).intersect(Span::new(4, 9)), Some(Span::new(4, 6)));
+        assert_eq!(Span::new(2, 9).intersect(Span::new(4, 6)), Some(Span::new(4, 6)));
+        assert_eq!(Span::new(2, 4).intersect(Span::new(4, 6)), Some(Span::empty(4)));
+        assert_eq!(Span::new(2, 4).intersect(Span::new(5, 6)), None);
+    }
+
+    #[test]
+    fn test_contains() {
+        let span = Span::new(2, 4);
+        assert!(!span.contains(1));
+        assert!(span.contains(2));
+        assert!(span.contains(3));
+        assert!(!span.contains(4));
+        assert!(!Span::empty(2).contains(2));
+        assert!(span.contains_span(Span::new(2, 4)));
+        assert!(span.contains_span(Span::empty(4)));
+        assert!(!span.contains_span(Span::new(3, 5)));
+    }
+
+    #[test]
+    fn test_char_range() {
+        let source = "é = \"😀\"";
+        let span = Span::new("é = \"".len(), source.len());
+        assert_eq!(span.text(source), "😀\"");
+        assert_eq!(span.char_range(source), 5..7);
+        assert_eq!(span.utf16_range(source), 5..8);
+        assert_eq!(Span::new(0, source.len()).char_range(source), 0..7);
+    }
+
+    #[test]
+    fn test_from_char_range() {
+        let source = "é = \"😀\"";
+        let span = Span::new("é = \"".len(), source.len());
+        assert_eq!(Span::from_char_range(source, 5..7), Some(span));
+        assert_eq!(Span::from_utf16_range(source, 5..8), Some(span));
+        assert_eq!(Span::from_utf16_range(source, 6..8), None);
+        assert_eq!(Span::from_char_range(source, 7..7), Some(Span::empty(source.len())));
+        assert_eq!(Span::from_char_range(source, 7..8), None);
+    }
+
+    #[test]
+    fn test_text() {
+        let source = "foo bar";
+        assert_eq!(Span::new(4, 7).text(source), "bar");
+        assert_eq!(Span::empty(3).text(source), "");
+    }
+}
@@ -0,0 +1,73 @@
+//! Changes to source code, such as the ones an editor reports while typing.
+
+use crate::span::Span;
+
+/// Replaces the text in a span of the source code with new text.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextEdit {
+    span: Span,
+    text: String,
+}
+
+impl TextEdit {
+    pub fn replace(span: Span, text: impl Into<String>) -> Self {
+        Self {
+            span,
+            text: text.into(),
+        }
+    }
+
+    pub fn insert(offset: usize, text: impl Into<String>) -> Self {
+        Self::replace(Span::empty(offset), text)
+    }
+
+    pub fn delete(span: Span) -> Self {
+        Self::replace(span, "")
+    }
+
+    /// Returns the span of the text which is replaced.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Returns the text which is inserted.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the source code with this edit applied.
+    ///
+    /// Panics if the span is outside the source code or does not lie on character boundaries.
+    pub fn apply(&self, source: &str) -> String {
+        let mut result = String::with_capacity(source.len() - self.span.length() + self.text.len());
+        result.push_str(&source[..self.span.start()]);
+        result.push_str(&self.text);
+        result.push_str(&source[self.span.end()..]);
+        result
+    }
+
+    /// Returns the span of the inserted text once this edit is applied.
+    pub fn inserted_span(&self) -> Span {
+        Span::new(self.span.start(), self.span.start() + self.text.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let source = "let x: Integer = 1;";
+        assert_eq!(TextEdit::replace(Span::new(4, 5), "yz").apply(source), "let yz: Integer = 1;");
+        assert_eq!(TextEdit::insert(source.len(), "\n").apply(source), "let x: Integer = 1;\n");
+        assert_eq!(TextEdit::delete(Span::new(14, 18)).apply(source), "let x: Integer;");
+    }
+
+    #[test]
+    fn test_inserted_span() {
+        let edit = TextEdit::replace(Span::new(4, 5), "yz");
+        assert_eq!(edit.inserted_span(), Span::new(4, 6));
+        assert_eq!(TextEdit::delete(Span::new(2, 6)).inserted_span(), Span::empty(2));
+    }
+}